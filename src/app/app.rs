@@ -15,13 +15,25 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use datafusion::prelude::ExecutionConfig;
-use log::debug;
+use log::{debug, error, info};
+use std::env;
+use std::path::PathBuf;
+use std::time::Instant;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 
+use crate::app::datafusion::commands::Command;
+use crate::app::datafusion::config::ConfigSetting;
 use crate::app::datafusion::context::{Context, QueryResults};
-use crate::app::editor::Editor;
+use crate::app::datafusion::optimizer::OptimizerRules;
+use crate::app::datafusion::plan::executed_plan_tree;
+use crate::app::datafusion::query::{spawn_query, QueryEvent, QueryStatus, RunningQuery};
+use crate::app::datafusion::script::split_statements;
+use crate::app::editor::{external, Editor, History, HistoryBrowser};
 use crate::app::handlers::key_event_handler;
+use crate::app::ui::file_picker::FilePicker;
+use crate::app::ui::tree::{Tree, TreeNode};
 use crate::cli::args::Args;
+use crate::cli::print_format::ExportFormat;
 use crate::events::Key;
 
 pub struct Tabs {
@@ -37,6 +49,8 @@ impl Tabs {
                 "Query History [1]",
                 "Context [2]",
                 "Logs [3]",
+                "Catalog [4]",
+                "Plan [5]",
             ],
             index: 0,
         }
@@ -48,10 +62,72 @@ pub enum InputMode {
     Editing,
 }
 
+/// What the text entered in a `Prompt` is used for
+pub enum PromptKind {
+    /// Filter the query results to rows containing the text
+    Filter,
+    /// Search the query history, applied as the text is typed
+    HistorySearch,
+    /// New name for the active editor buffer
+    RenameBuffer,
+    /// Path to save the active editor buffer to
+    SavePath,
+    /// New value of an `ExecutionConfig` setting
+    ConfigValue(ConfigSetting),
+    /// Confirm closing the active buffer, which has unsaved changes
+    ConfirmClose,
+    /// Confirm quitting while buffers have unsaved changes
+    ConfirmQuit,
+    /// Path to export the query results to
+    ExportPath,
+    /// Format to export the query results to `path` in
+    ExportFormat { path: String },
+}
+
+/// Single line of input shown in place of the help message
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: String,
+}
+
+impl Prompt {
+    pub fn new(kind: PromptKind) -> Prompt {
+        Prompt::with_input(kind, String::new())
+    }
+
+    pub fn with_input(kind: PromptKind, input: String) -> Prompt {
+        Prompt { kind, input }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self.kind {
+            PromptKind::Filter => "Filter rows: ",
+            PromptKind::HistorySearch => "Search history: ",
+            PromptKind::RenameBuffer => "Rename buffer: ",
+            PromptKind::SavePath => "Save buffer to: ",
+            PromptKind::ConfigValue(_) => "New value: ",
+            PromptKind::ConfirmClose => "Discard unsaved changes to this buffer? (y/N): ",
+            PromptKind::ConfirmQuit => "Discard unsaved changes and quit? (y/N): ",
+            PromptKind::ExportPath => "Export results to: ",
+            PromptKind::ExportFormat { .. } => {
+                "Export format (csv, tsv, table, json, nd-json, parquet, arrow): "
+            }
+        }
+    }
+}
+
+/// Outcome of the last command, shown after the help message
+pub struct StatusMessage {
+    pub text: String,
+    pub is_error: bool,
+}
+
 #[derive(PartialEq)]
 pub enum AppReturn {
     Continue,
     Exit,
+    /// Suspend the TUI and edit the active buffer with `$VISUAL` or `$EDITOR`
+    EditExternally,
 }
 
 /// App holds the state of the application
@@ -60,23 +136,53 @@ pub struct App {
     pub tabs: Tabs,
     /// Current input mode
     pub input_mode: InputMode,
+    /// Prompt receiving input, takes precedence over the input mode
+    pub prompt: Option<Prompt>,
+    /// Message reporting the outcome of the last command
+    pub status_message: Option<StatusMessage>,
+    /// Picker for a file to open, takes precedence over the prompt
+    pub file_picker: Option<FilePicker>,
+    /// Directory the file picker starts in and relative save paths are resolved against
+    pub file_root: PathBuf,
     /// SQL Editor and it's state
     pub editor: Editor,
+    /// Selection and search of the Query History tab
+    pub history_browser: HistoryBrowser,
     /// DataFusion `ExecutionContext`
     pub context: Context,
-    /// Results from DataFusion query
-    pub query_results: Option<QueryResults>,
+    /// Index of the selected item in the Context tab, counting the settings followed by the
+    /// logical and then the physical optimizer rules
+    pub context_selected: usize,
+    /// Optimizer rules of the context and whether each is enabled
+    pub optimizer_rules: OptimizerRules,
+    /// Catalogs, schemas and tables of the context
+    pub catalog: Tree,
+    /// Plans of the query in the editor
+    pub plan: Tree,
+    /// Reason the plans of the query in the editor could not be built
+    pub plan_error: Option<String>,
+    /// Maximum number of rows retained in each buffer's `query_results`
+    pub max_display_rows: usize,
+    /// Whether query durations are shown with the results, toggled by `\timing`
+    pub timing: bool,
+    /// Format suggested when exporting results, set by `\format`
+    pub export_format: ExportFormat,
+    /// Query currently executing in the background
+    running_query: Option<RunningQuery>,
+    /// Id of the buffer the running query was run from
+    running_buffer: usize,
+    /// Id assigned to the next submitted query
+    next_query_id: usize,
+    /// Sender passed to query tasks to report their outcome
+    query_tx: UnboundedSender<(usize, QueryEvent)>,
+    /// Receiver for query outcomes, drained on each tick
+    query_rx: UnboundedReceiver<(usize, QueryEvent)>,
 }
 
 impl App {
     pub async fn new(args: Args) -> App {
-        let execution_config = ExecutionConfig::new().with_information_schema(true);
-        let mut ctx: Context = match (args.host, args.port) {
-            (Some(ref h), Some(p)) => Context::new_remote(h, p).unwrap(),
-            _ => Context::new_local(&execution_config).await,
-        };
+        let mut ctx = Context::from_args(&args).await;
 
-        let files = args.file;
         let rc = match args.rc {
             Some(file) => file,
             None => {
@@ -92,29 +198,372 @@ impl App {
             }
         };
 
-        if !files.is_empty() {
-            ctx.exec_files(files).await
-        } else {
-            if !rc.is_empty() {
-                ctx.exec_files(rc).await
+        if !rc.is_empty() {
+            ctx.exec_files(rc).await
+        }
+
+        let mut editor = Editor::default();
+        if args.max_history > 0 {
+            if let Some(path) = History::default_path() {
+                editor.history = History::load(path, args.max_history);
             }
         }
 
+        let file_root = match args.data_path {
+            Some(ref path) => PathBuf::from(path),
+            None => env::current_dir().unwrap_or_default(),
+        };
+
+        let optimizer_rules = ctx.optimizer_rules();
+        let (query_tx, query_rx) = mpsc::unbounded_channel();
+
         App {
             tabs: Tabs::new(),
             input_mode: InputMode::Normal,
-            editor: Editor::default(),
+            prompt: None,
+            status_message: None,
+            file_picker: None,
+            file_root,
+            editor,
+            history_browser: HistoryBrowser::default(),
             context: ctx,
-            query_results: None,
+            context_selected: 0,
+            optimizer_rules,
+            catalog: Tree::new(Vec::new()),
+            plan: Tree::new(Vec::new()),
+            plan_error: None,
+            max_display_rows: args.max_display_rows,
+            timing: !args.quiet,
+            export_format: args.format.into(),
+            running_query: None,
+            running_buffer: 0,
+            next_query_id: 0,
+            query_tx,
+            query_rx,
+        }
+    }
+
+    /// Execute the statements in `sql` in the background for the active buffer, results are
+    /// picked up in `update_on_tick`
+    pub fn run_query(&mut self, sql: String) {
+        if self.is_query_running() {
+            info!("A query is already running, wait for it to finish");
+            return;
+        }
+        let statements = split_statements(&sql);
+        if statements.is_empty() {
+            info!("No statements to run");
+            return;
+        }
+        let id = self.next_query_id;
+        self.next_query_id += 1;
+        let query = spawn_query(
+            id,
+            self.context.clone(),
+            statements,
+            self.max_display_rows,
+            self.query_tx.clone(),
+        );
+        let buffer = self.editor.buffer_mut();
+        buffer.query_results = None;
+        buffer.statements.clear();
+        buffer.executed_plan = None;
+        buffer.query_status = QueryStatus::Running(query.started);
+        self.running_buffer = buffer.id;
+        self.running_query = Some(query);
+    }
+
+    /// Run a meta-command entered in the editor, listings are shown as the active buffer's
+    /// query results
+    pub fn run_command(&mut self, command: Command) -> AppReturn {
+        match command {
+            Command::Quit => return self.quit(),
+            Command::Timing(on) => {
+                self.timing = on.unwrap_or(!self.timing);
+                let state = if self.timing { "on" } else { "off" };
+                self.set_status(format!("Timing is {}", state), false);
+            }
+            Command::Format(None) => {
+                let name = self.export_format.name();
+                self.set_status(format!("Output format is {}", name), false);
+            }
+            Command::Format(Some(name)) => match name.parse::<ExportFormat>() {
+                Ok(format) => {
+                    self.set_status(format!("Output format is {}", format.name()), false);
+                    self.export_format = format;
+                }
+                Err(e) => self.set_status(format!("Invalid output format: {}", e), true),
+            },
+            _ if self.is_query_running() && self.running_buffer == self.editor.buffer().id => {
+                info!("Wait for the query to finish before running a command")
+            }
+            command => {
+                let started = Instant::now();
+                let mut results = QueryResults::new(self.editor.input().combine_lines());
+                let batch = command.results(&self.context);
+                let buffer = self.editor.buffer_mut();
+                buffer.statements.clear();
+                buffer.executed_plan = None;
+                match batch {
+                    Ok(batch) => {
+                        results.meta.rows = batch.num_rows();
+                        results.meta.succeeded = true;
+                        results.meta.query_duration = started.elapsed().as_secs_f64();
+                        results.batches.push(batch);
+                        buffer.query_results = Some(results);
+                        buffer.query_status = QueryStatus::Finished;
+                    }
+                    Err(e) => {
+                        buffer.query_results = None;
+                        buffer.query_status = QueryStatus::Failed(e.to_string());
+                    }
+                }
+            }
+        }
+        AppReturn::Continue
+    }
+
+    pub fn is_query_running(&self) -> bool {
+        self.running_query.is_some()
+    }
+
+    /// Abort the running query, if any, and record it as cancelled in the history
+    pub fn cancel_query(&mut self) {
+        if let Some(query) = self.running_query.take() {
+            let meta = query.cancel();
+            self.editor.history.push(meta);
+            if let Some(buffer) = self.editor.buffer_by_id_mut(self.running_buffer) {
+                buffer.query_status = QueryStatus::Cancelled;
+            }
+        }
+    }
+
+    /// Change an `ExecutionConfig` setting of the context, logging the change
+    pub fn set_config(&mut self, setting: ConfigSetting, value: String) {
+        match self.context.set_config(setting, &value) {
+            Ok(()) => self.set_status(format!("Set {} to {}", setting.name(), value.trim()), false),
+            Err(e) => self.set_status(format!("Unable to set {}: {}", setting.name(), e), true),
+        }
+    }
+
+    /// Enable or disable the optimizer rule at `index` of `optimizer_rules`, logging the change
+    pub fn toggle_optimizer_rule(&mut self, index: usize) {
+        let (name, enabled) = match self.optimizer_rules.toggle(index) {
+            Some(toggled) => toggled,
+            None => return,
+        };
+        match self.context.set_optimizer_rules(&self.optimizer_rules) {
+            Ok(()) => {
+                let action = if enabled { "Enabled" } else { "Disabled" };
+                self.set_status(format!("{} optimizer rule {}", action, name), false)
+            }
+            Err(e) => {
+                // Keep the rules in step with the context
+                self.optimizer_rules.toggle(index);
+                self.set_status(format!("Unable to change optimizer rules: {}", e), true)
+            }
+        }
+    }
+
+    /// Rebuild the catalog tree from the context, keeping the selection where possible
+    pub fn refresh_catalog(&mut self) {
+        let roots = self.context.catalog_tree().unwrap_or_default();
+        let selected = self.catalog.selected;
+        self.catalog = Tree::new(roots);
+        for root in &mut self.catalog.roots {
+            // Catalogs and schemas are expanded so tables are visible straight away
+            root.expanded = true;
+            for schema in &mut root.children {
+                schema.expanded = true;
+            }
         }
+        self.catalog.selected = selected.min(self.catalog.visible().len().saturating_sub(1));
+    }
+
+    /// Rebuild the plans of the last statement in the active buffer, followed by the metrics
+    /// of the last query run from it
+    pub async fn refresh_plan(&mut self) {
+        let sql = self.editor.input().combine_lines();
+        let mut roots = Vec::new();
+        self.plan_error = None;
+        if let Some(statement) = split_statements(&sql).pop() {
+            match self.context.plan_trees(&statement).await {
+                Ok(plans) => roots.extend(plans),
+                Err(e) => self.plan_error = Some(e.to_string()),
+            }
+        }
+        if let Some(ref plan) = self.editor.buffer().executed_plan {
+            let mut root = TreeNode::branch(
+                String::from("Executed Plan of the last query run"),
+                vec![executed_plan_tree(plan.as_ref())],
+            );
+            root.expanded = true;
+            roots.push(root);
+        }
+        self.plan = Tree::new(roots);
+    }
+
+    /// Replace the active buffer with the query edited in an external editor, the terminal
+    /// must be released by the caller
+    pub fn edit_externally(&mut self) {
+        match external::edit_externally(&self.editor.input().combine_lines()) {
+            Ok(query) => {
+                self.editor.load_query(&query);
+                self.set_status(String::from("Loaded query from external editor"), false)
+            }
+            Err(e) => self.set_status(format!("Unable to edit query: {}", e), true),
+        }
+    }
+
+    /// Show the file picker, starting in the directory of the active buffer's file if it has
+    /// one
+    pub fn open_file_picker(&mut self) {
+        let dir = match self.editor.buffer().path {
+            Some(ref path) => path.parent().map(|p| p.to_path_buf()),
+            None => None,
+        };
+        let dir = dir.unwrap_or_else(|| self.file_root.clone());
+        match FilePicker::new(dir) {
+            Ok(picker) => self.file_picker = Some(picker),
+            Err(e) => self.set_status(format!("Unable to list files: {}", e), true),
+        }
+    }
+
+    pub fn open_file(&mut self, path: PathBuf) {
+        match self.editor.open_file(path.clone()) {
+            Ok(()) => {
+                self.tabs.index = 0;
+                self.set_status(format!("Opened {}", path.display()), false)
+            }
+            Err(e) => self.set_status(format!("Unable to open {}: {}", path.display(), e), true),
+        }
+    }
+
+    /// Save the active buffer to its file, prompting for a path if it has never been saved
+    pub fn save_buffer(&mut self) {
+        match self.editor.buffer().path.clone() {
+            Some(path) => self.save_buffer_as(path),
+            None => self.prompt_save_path(),
+        }
+    }
+
+    /// Prompt for the path to save the active buffer to, starting from its current file or
+    /// `file_root`
+    pub fn prompt_save_path(&mut self) {
+        let path = match self.editor.buffer().path {
+            Some(ref path) => path.clone(),
+            None => self.file_root.join(""),
+        };
+        let input = path.display().to_string();
+        self.prompt = Some(Prompt::with_input(PromptKind::SavePath, input));
+    }
+
+    /// Save the active buffer to `path`, relative paths are resolved against `file_root`
+    pub fn save_buffer_as(&mut self, path: PathBuf) {
+        let path = self.file_root.join(path);
+        match self.editor.buffer_mut().save(path.clone()) {
+            Ok(()) => self.set_status(format!("Saved {}", path.display()), false),
+            Err(e) => self.set_status(format!("Unable to save {}: {}", path.display(), e), true),
+        }
+    }
+
+    /// Close the active buffer, asking for confirmation if it has unsaved changes
+    pub fn close_buffer(&mut self) {
+        if !self.editor.close_buffer() {
+            self.prompt = Some(Prompt::new(PromptKind::ConfirmClose));
+        }
+    }
+
+    /// Exit the app, asking for confirmation if any buffer has unsaved changes
+    pub fn quit(&mut self) -> AppReturn {
+        if self.editor.is_dirty() {
+            self.prompt = Some(Prompt::new(PromptKind::ConfirmQuit));
+            return AppReturn::Continue;
+        }
+        AppReturn::Exit
+    }
+
+    /// Report the outcome of a command in the status line and the logs
+    pub fn set_status(&mut self, text: String, is_error: bool) {
+        if is_error {
+            error!("{}", text);
+        } else {
+            info!("{}", text);
+        }
+        self.status_message = Some(StatusMessage { text, is_error });
     }
 
     pub async fn key_handler(&mut self, key: Key) -> AppReturn {
         debug!("Key event: {:?}", key);
+        // The status only reports the outcome of the previous key
+        self.status_message = None;
         key_event_handler(self, key).await.unwrap()
     }
 
     pub fn update_on_tick(&mut self) -> AppReturn {
+        while let Ok((id, event)) = self.query_rx.try_recv() {
+            match self.running_query {
+                Some(ref query) if query.id == id => self.handle_query_event(event),
+                // Outcome of a cancelled query that completed before it was aborted
+                _ => debug!("Ignoring event from query {}", id),
+            }
+        }
         AppReturn::Continue
     }
+
+    fn handle_query_event(&mut self, event: QueryEvent) {
+        match event {
+            QueryEvent::Batch { batch, rows } => {
+                if let Some(results) = self.running_query_results() {
+                    if let Some(batch) = batch {
+                        results.batches.push(batch);
+                    }
+                    results.meta.rows = rows;
+                }
+            }
+            QueryEvent::StatementFinished(meta) => {
+                self.editor.history.push(meta.clone());
+                if let Some(buffer) = self.editor.buffer_by_id_mut(self.running_buffer) {
+                    buffer.statements.push(meta);
+                }
+            }
+            QueryEvent::Finished(meta, plan) => {
+                self.editor.history.push(meta.clone());
+                if let Some(buffer) = self.editor.buffer_by_id_mut(self.running_buffer) {
+                    buffer.executed_plan = plan;
+                }
+                if let Some(results) = self.running_query_results() {
+                    results.meta = meta;
+                }
+                self.finish_query(QueryStatus::Finished);
+            }
+            QueryEvent::Failed(meta) => {
+                let err_msg = meta.error.clone().unwrap_or_default();
+                self.editor.history.push(meta);
+                self.finish_query(QueryStatus::Failed(err_msg));
+            }
+        }
+    }
+
+    fn finish_query(&mut self, status: QueryStatus) {
+        if let Some(buffer) = self.editor.buffer_by_id_mut(self.running_buffer) {
+            buffer.query_status = status;
+        }
+        self.running_query = None;
+    }
+
+    /// Results of the running query, created when its first event arrives.  `None` if the
+    /// buffer the query was run from has been closed.
+    fn running_query_results(&mut self) -> Option<&mut QueryResults> {
+        let query = match self.running_query {
+            Some(ref q) => q.sql.clone(),
+            None => String::new(),
+        };
+        let buffer = self.editor.buffer_by_id_mut(self.running_buffer)?;
+        Some(
+            buffer
+                .query_results
+                .get_or_insert_with(|| QueryResults::new(query)),
+        )
+    }
 }
@@ -0,0 +1,227 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! psql style meta-commands, entered in the editor in place of SQL
+use arrow::array::{ArrayRef, StringArray, UInt64Array};
+use arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result};
+use std::sync::Arc;
+
+use crate::app::datafusion::context::Context;
+
+/// Meta-commands with their usage, listed by `\?`
+const USAGE: &[(&str, &str)] = &[
+    ("\\?", "List the meta-commands"),
+    ("\\d", "List tables"),
+    ("\\dt", "List tables"),
+    ("\\d NAME", "Describe the columns of table NAME"),
+    ("\\df", "List functions"),
+    ("\\timing [on|off]", "Toggle showing how long queries took"),
+    (
+        "\\format [FORMAT]",
+        "Show or set the default format of exported results",
+    ),
+    ("\\q", "Quit"),
+    ("\\quit", "Quit"),
+];
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Help,
+    ListTables,
+    DescribeTable(String),
+    ListFunctions,
+    /// Turn timing on or off, or toggle it when `None`
+    Timing(Option<bool>),
+    /// Set the output format, or show it when `None`
+    Format(Option<String>),
+    Quit,
+}
+
+impl Command {
+    /// Parse `input` as a meta-command, returns `None` if it isn't one, i.e. doesn't start with
+    /// a backslash.  A trailing semicolon is ignored.
+    pub fn parse(input: &str) -> Option<std::result::Result<Command, String>> {
+        let input = input.trim();
+        let input = input.strip_suffix(';').unwrap_or(input);
+        let command = input.strip_prefix('\\')?;
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let arg = words.next();
+        if words.next().is_some() {
+            return Some(Err(format!("Too many arguments to \\{}", name)));
+        }
+        let command = match (name, arg) {
+            ("?", None) => Command::Help,
+            ("d", None) | ("dt", None) => Command::ListTables,
+            ("d", Some(table)) => Command::DescribeTable(table.to_string()),
+            ("df", None) => Command::ListFunctions,
+            ("timing", None) => Command::Timing(None),
+            ("timing", Some(arg)) => match arg.to_lowercase().as_str() {
+                "on" => Command::Timing(Some(true)),
+                "off" => Command::Timing(Some(false)),
+                _ => return Some(Err(format!("Expected on or off, got {}", arg))),
+            },
+            ("format", arg) => Command::Format(arg.map(|f| f.to_string())),
+            ("q", None) | ("quit", None) => Command::Quit,
+            ("?", _) | ("dt", _) | ("df", _) | ("q", _) | ("quit", _) => {
+                return Some(Err(format!("\\{} takes no arguments", name)))
+            }
+            _ => {
+                return Some(Err(format!(
+                    "Invalid command \\{}, try \\? for a list of commands",
+                    name
+                )))
+            }
+        };
+        Some(Ok(command))
+    }
+
+    /// Output of commands listing information, shown in the results pane.  Other commands
+    /// have no output and return an error.
+    pub fn results(&self, ctx: &Context) -> Result<RecordBatch> {
+        let batch = match self {
+            Command::Help => RecordBatch::try_from_iter(vec![
+                ("command", strings(USAGE.iter().map(|(c, _)| c.to_string()))),
+                (
+                    "description",
+                    strings(USAGE.iter().map(|(_, d)| d.to_string())),
+                ),
+            ]),
+            Command::ListTables => {
+                let tables = ctx.tables();
+                let columns: Vec<u64> = tables
+                    .iter()
+                    .map(|(_, s)| s.fields().len() as u64)
+                    .collect();
+                RecordBatch::try_from_iter(vec![
+                    (
+                        "table_name",
+                        strings(tables.into_iter().map(|(name, _)| name)),
+                    ),
+                    ("columns", Arc::new(UInt64Array::from(columns)) as ArrayRef),
+                ])
+            }
+            Command::DescribeTable(table) => {
+                // Tables outside the default schema can also be named without their qualifier
+                let tables = ctx.tables();
+                let qualified = format!(".{}", table.to_lowercase());
+                let schema = match tables
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(table))
+                    .or_else(|| {
+                        tables
+                            .iter()
+                            .find(|(name, _)| name.to_lowercase().ends_with(&qualified))
+                    })
+                    .map(|(_, schema)| schema.clone())
+                {
+                    Some(schema) => schema,
+                    None => {
+                        return Err(DataFusionError::Plan(format!(
+                            "Table '{}' not found",
+                            table
+                        )))
+                    }
+                };
+                let fields = schema.fields();
+                RecordBatch::try_from_iter(vec![
+                    (
+                        "column_name",
+                        strings(fields.iter().map(|f| f.name().clone())),
+                    ),
+                    (
+                        "data_type",
+                        strings(fields.iter().map(|f| f.data_type().to_string())),
+                    ),
+                    (
+                        "is_nullable",
+                        strings(
+                            fields
+                                .iter()
+                                .map(|f| if f.is_nullable() { "YES" } else { "NO" }.to_string()),
+                        ),
+                    ),
+                ])
+            }
+            Command::ListFunctions => RecordBatch::try_from_iter(vec![(
+                "function_name",
+                strings(ctx.function_names().into_iter()),
+            )]),
+            Command::Timing(_) | Command::Format(_) | Command::Quit => {
+                return Err(DataFusionError::Internal(format!(
+                    "{:?} has no results to show",
+                    self
+                )))
+            }
+        };
+        batch.map_err(DataFusionError::from)
+    }
+}
+
+fn strings(values: impl Iterator<Item = String>) -> ArrayRef {
+    Arc::new(values.map(Some).collect::<StringArray>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::execution::context::ExecutionConfig;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(None, Command::parse("SELECT 1;"));
+        assert_eq!(Some(Ok(Command::ListTables)), Command::parse(" \\dt "));
+        assert_eq!(Some(Ok(Command::ListTables)), Command::parse("\\d;"));
+        assert_eq!(
+            Some(Ok(Command::DescribeTable("t".to_string()))),
+            Command::parse("\\d t")
+        );
+        assert_eq!(
+            Some(Ok(Command::Timing(Some(false)))),
+            Command::parse("\\timing OFF")
+        );
+        assert_eq!(
+            Some(Ok(Command::Format(Some("csv".to_string())))),
+            Command::parse("\\format csv")
+        );
+        assert_eq!(Some(Ok(Command::Quit)), Command::parse("\\q"));
+        assert!(matches!(Command::parse("\\dx"), Some(Err(_))));
+        assert!(matches!(Command::parse("\\q now"), Some(Err(_))));
+        assert!(matches!(Command::parse("\\timing maybe"), Some(Err(_))));
+    }
+
+    #[tokio::test]
+    async fn test_describe_table() {
+        let mut ctx =
+            Context::new_local(&ExecutionConfig::new().with_information_schema(true)).await;
+        ctx.sql("CREATE TABLE t AS SELECT 1 AS a, 'x' AS b")
+            .await
+            .unwrap();
+        let batch = Command::DescribeTable("T".to_string())
+            .results(&ctx)
+            .unwrap();
+        assert_eq!(2, batch.num_rows());
+        assert!(Command::DescribeTable("columns".to_string())
+            .results(&ctx)
+            .is_ok());
+        assert!(Command::DescribeTable("missing".to_string())
+            .results(&ctx)
+            .is_err());
+        assert!(Command::Quit.results(&ctx).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Settings of the `ExecutionConfig` that can be changed from the Context tab
+
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::ExecutionConfig;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigSetting {
+    TargetPartitions,
+    BatchSize,
+    RepartitionJoins,
+    RepartitionAggregations,
+    RepartitionWindows,
+    InformationSchema,
+}
+
+impl ConfigSetting {
+    /// Settings in the order they are listed
+    pub const ALL: [ConfigSetting; 6] = [
+        ConfigSetting::TargetPartitions,
+        ConfigSetting::BatchSize,
+        ConfigSetting::RepartitionJoins,
+        ConfigSetting::RepartitionAggregations,
+        ConfigSetting::RepartitionWindows,
+        ConfigSetting::InformationSchema,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigSetting::TargetPartitions => "Target Partitions",
+            ConfigSetting::BatchSize => "Batch Size",
+            ConfigSetting::RepartitionJoins => "Repartition Joins",
+            ConfigSetting::RepartitionAggregations => "Repartition Aggregations",
+            ConfigSetting::RepartitionWindows => "Repartition Windows",
+            ConfigSetting::InformationSchema => "Information Schema",
+        }
+    }
+
+    /// Whether the setting is on or off, rather than a number
+    pub fn is_flag(&self) -> bool {
+        !matches!(
+            self,
+            ConfigSetting::TargetPartitions | ConfigSetting::BatchSize
+        )
+    }
+
+    /// `config` with this setting changed to `value`
+    pub fn apply(&self, config: ExecutionConfig, value: &str) -> Result<ExecutionConfig> {
+        let value = value.trim();
+        let config = match self {
+            ConfigSetting::TargetPartitions => {
+                config.with_target_partitions(parse_positive(self, value)?)
+            }
+            ConfigSetting::BatchSize => config.with_batch_size(parse_positive(self, value)?),
+            ConfigSetting::RepartitionJoins => {
+                config.with_repartition_joins(parse_flag(self, value)?)
+            }
+            ConfigSetting::RepartitionAggregations => {
+                config.with_repartition_aggregations(parse_flag(self, value)?)
+            }
+            ConfigSetting::RepartitionWindows => {
+                config.with_repartition_windows(parse_flag(self, value)?)
+            }
+            ConfigSetting::InformationSchema => {
+                config.with_information_schema(parse_flag(self, value)?)
+            }
+        };
+        Ok(config)
+    }
+}
+
+fn parse_positive(setting: &ConfigSetting, value: &str) -> Result<usize> {
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(DataFusionError::Plan(format!(
+            "{} must be a positive integer, got '{}'",
+            setting.name(),
+            value
+        ))),
+    }
+}
+
+fn parse_flag(setting: &ConfigSetting, value: &str) -> Result<bool> {
+    value.parse::<bool>().map_err(|_| {
+        DataFusionError::Plan(format!(
+            "{} must be true or false, got '{}'",
+            setting.name(),
+            value
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_settings() {
+        let config = ExecutionConfig::new();
+        let config = ConfigSetting::TargetPartitions
+            .apply(config, " 3 ")
+            .unwrap();
+        assert_eq!(3, config.target_partitions);
+        let config = ConfigSetting::BatchSize.apply(config, "1024").unwrap();
+        assert_eq!(1024, config.runtime.batch_size);
+        let config = ConfigSetting::RepartitionJoins
+            .apply(config, "false")
+            .unwrap();
+        assert!(!config.repartition_joins);
+
+        let config = ExecutionConfig::new();
+        assert!(ConfigSetting::TargetPartitions.apply(config, "0").is_err());
+        let config = ExecutionConfig::new();
+        assert!(ConfigSetting::RepartitionWindows
+            .apply(config, "yes")
+            .is_err());
+    }
+}
@@ -17,45 +17,264 @@
 
 //! Context (remote or local)
 
+use arrow::datatypes::{Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Utc};
+use datafusion::catalog::catalog::CatalogList;
 use datafusion::dataframe::DataFrame;
 use datafusion::error::{DataFusionError, Result};
 use datafusion::execution::context::{ExecutionConfig, ExecutionContext};
+use datafusion::execution::runtime_env::RuntimeEnv;
+use datafusion::logical_plan::LogicalPlan;
+use datafusion::physical_plan::ExecutionPlan;
 
-use log::{debug, info};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::cmp;
+use std::fs;
 use std::sync::Arc;
+use std::time::Instant;
 
+use crate::app::datafusion::config::ConfigSetting;
+use crate::app::datafusion::data_path::register_data_path;
+use crate::app::datafusion::optimizer::{default_logical_rules, OptimizerRules};
+use crate::app::datafusion::plan::{logical_plan_tree, physical_plan_tree};
+use crate::app::datafusion::script::split_statements;
+use crate::app::datafusion::view;
+use crate::app::ui::inspector::CellInspector;
+use crate::app::ui::tree::TreeNode;
 use crate::app::ui::Scroll;
+use crate::cli::args::Args;
+use crate::cli::print_options::PrintOptions;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct QueryResultsMeta {
     pub query: String,
     pub succeeded: bool,
     pub error: Option<String>,
     pub rows: usize,
     pub query_duration: f64,
+    /// When the query was submitted
+    pub executed_at: DateTime<Utc>,
 }
 
 pub struct QueryResults {
     pub batches: Vec<RecordBatch>,
     pub meta: QueryResultsMeta,
     pub scroll: Scroll,
+    /// Cell under the cursor
+    pub selection: Scroll,
+    /// Number of rows visible in the results table when it was last drawn
+    pub page_size: usize,
+    /// Popup with the full value of the selected cell
+    pub inspector: Option<CellInspector>,
+    /// Column index and whether the results are sorted descending
+    pub sort: Option<(usize, bool)>,
+    /// Only rows containing this text are shown
+    pub filter: Option<String>,
+    /// Batches as returned by the query, kept while a sort or filter is applied
+    unsorted_batches: Option<Vec<RecordBatch>>,
 }
 
 impl QueryResults {
-    pub fn format_timing_info(&self) -> String {
+    /// Empty results for `query`, batches are appended as they are streamed in
+    pub fn new(query: String) -> QueryResults {
+        QueryResults {
+            batches: Vec::new(),
+            meta: QueryResultsMeta {
+                query,
+                succeeded: false,
+                error: None,
+                rows: 0,
+                query_duration: 0.0,
+                executed_at: Utc::now(),
+            },
+            scroll: Scroll { x: 0, y: 0 },
+            selection: Scroll { x: 0, y: 0 },
+            page_size: 0,
+            inspector: None,
+            sort: None,
+            filter: None,
+            unsorted_batches: None,
+        }
+    }
+
+    /// Number of rows held in `batches`, which may be fewer than `meta.rows`
+    pub fn retained_rows(&self) -> usize {
+        self.batches.iter().map(|b| b.num_rows()).sum()
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.batches.first().map(|b| b.num_columns()).unwrap_or(0)
+    }
+
+    /// Locate `row` within `batches`, returning its batch and the offset within that batch
+    pub fn locate_row(&self, row: usize) -> Option<(&RecordBatch, usize)> {
+        let mut offset = row;
+        for batch in &self.batches {
+            if offset < batch.num_rows() {
+                return Some((batch, offset));
+            }
+            offset -= batch.num_rows();
+        }
+        None
+    }
+
+    pub fn move_down(&mut self, rows: usize) {
+        let last = self.retained_rows().saturating_sub(1);
+        self.selection.y = cmp::min(self.selection.y + rows, last);
+        self.follow_selection();
+    }
+
+    pub fn move_up(&mut self, rows: usize) {
+        self.selection.y = self.selection.y.saturating_sub(rows);
+        self.follow_selection();
+    }
+
+    pub fn move_to_top(&mut self) {
+        self.move_up(self.selection.y);
+    }
+
+    pub fn move_to_bottom(&mut self) {
+        self.move_down(self.retained_rows());
+    }
+
+    pub fn move_right(&mut self) {
+        if self.selection.x + 1 < self.num_columns() {
+            self.selection.x += 1;
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        self.selection.x = self.selection.x.saturating_sub(1);
+        self.scroll.x = cmp::min(self.scroll.x, self.selection.x);
+    }
+
+    /// Scroll vertically so the selected row is visible.  Columns are scrolled when drawn as
+    /// the number of visible columns depends on their widths.
+    fn follow_selection(&mut self) {
+        if self.selection.y < self.scroll.y {
+            self.scroll.y = self.selection.y;
+        } else if self.page_size > 0 && self.selection.y >= self.scroll.y + self.page_size {
+            self.scroll.y = self.selection.y + 1 - self.page_size;
+        }
+    }
+
+    /// Open the cell inspector for the selected cell
+    pub fn inspect(&mut self) {
+        self.inspector = CellInspector::new(self);
+    }
+
+    /// Cycle the selected column through ascending, descending and no sort
+    pub fn toggle_sort(&mut self) -> Result<()> {
+        let column = self.selection.x;
+        self.sort = match self.sort {
+            Some((c, false)) if c == column => Some((column, true)),
+            Some((c, true)) if c == column => None,
+            _ => Some((column, false)),
+        };
+        self.refresh_view()
+    }
+
+    pub fn set_filter(&mut self, filter: Option<String>) -> Result<()> {
+        self.filter = filter;
+        self.refresh_view()
+    }
+
+    /// Rebuild `batches` from the query's batches with the current sort and filter applied
+    fn refresh_view(&mut self) -> Result<()> {
+        if self.sort.is_none() && self.filter.is_none() {
+            if let Some(batches) = self.unsorted_batches.take() {
+                self.batches = batches;
+            }
+        } else {
+            let source = self
+                .unsorted_batches
+                .get_or_insert_with(|| self.batches.clone());
+            if source.is_empty() {
+                return Ok(());
+            }
+            let mut batch = view::concat_batches(source)?;
+            if let Some(ref filter) = self.filter {
+                batch = view::filter_batch(&batch, filter)?;
+            }
+            if let Some((column, descending)) = self.sort {
+                batch = view::sort_batch(&batch, column, descending)?;
+            }
+            self.batches = vec![batch];
+        }
+        self.selection.y = 0;
+        self.scroll.y = 0;
+        Ok(())
+    }
+
+    pub fn format_view_info(&self) -> String {
+        let mut info = String::new();
+        if let Some((column, descending)) = self.sort {
+            let name = match self.batches.first() {
+                Some(batch) => batch.schema().field(column).name().clone(),
+                None => column.to_string(),
+            };
+            let order = if descending { "desc" } else { "asc" };
+            info.push_str(&format!("[ Sorted by {} {} ] ", name, order));
+        }
+        if let Some(ref filter) = self.filter {
+            info.push_str(&format!(
+                "[ Filter '{}': {} matching ] ",
+                filter,
+                self.retained_rows()
+            ));
+        }
+        info
+    }
+
+    pub fn format_row_count(&self) -> String {
         format!(
-            "[ {} {} in set. Query took {:.3} seconds ] ",
+            "[ {} {} so far ] ",
+            self.meta.rows,
+            if self.meta.rows == 1 { "row" } else { "rows" }
+        )
+    }
+
+    /// Rows retained for display, before any filter is applied
+    fn retained_unfiltered_rows(&self) -> usize {
+        self.unsorted_batches
+            .as_ref()
+            .unwrap_or(&self.batches)
+            .iter()
+            .map(|b| b.num_rows())
+            .sum()
+    }
+
+    /// Whether the query produced more rows than were retained for display
+    pub fn is_truncated(&self) -> bool {
+        self.retained_unfiltered_rows() < self.meta.rows
+    }
+
+    /// Summary of the rows in the results, with how long the query took if `timing` is set
+    pub fn format_timing_info(&self, timing: bool) -> String {
+        let truncated = if self.is_truncated() {
+            format!(" Showing first {}.", self.retained_unfiltered_rows())
+        } else {
+            String::new()
+        };
+        let took = if timing {
+            format!(" Query took {:.3} seconds", self.meta.query_duration)
+        } else {
+            String::new()
+        };
+        format!(
+            "[ {} {} in set.{}{} ] ",
             self.meta.rows,
             if self.meta.rows == 1 { "row" } else { "rows" },
-            self.meta.query_duration
+            truncated,
+            took
         )
     }
 }
 
 /// The CLI supports using a local DataFusion context or a distributed BallistaContext
+#[derive(Clone)]
 pub enum Context {
     /// In-process execution with DataFusion
     Local(ExecutionContext),
@@ -70,6 +289,25 @@ impl Context {
         Ok(Context::Remote(BallistaContext::try_new(host, port)?))
     }
 
+    /// create a remote context if a Ballista host and port were provided, else a local one
+    pub async fn from_args(args: &Args) -> Context {
+        let mut execution_config = ExecutionConfig::new().with_information_schema(true);
+        if let Some(batch_size) = args.batch_size {
+            execution_config = execution_config.with_batch_size(batch_size);
+        }
+        let mut ctx = match (&args.host, args.port) {
+            (Some(h), Some(p)) => Context::new_remote(h, p).unwrap(),
+            _ => Context::new_local(&execution_config).await,
+        };
+        if let Some(ref data_path) = args.data_path {
+            match ctx {
+                Context::Local(ref mut ctx) => register_data_path(ctx, data_path).await,
+                Context::Remote(_) => info!("Data path is only registered with local contexts"),
+            }
+        }
+        ctx
+    }
+
     /// create a local context using the given config
     pub async fn new_local(config: &ExecutionConfig) -> Context {
         debug!("Created ExecutionContext");
@@ -88,105 +326,518 @@ impl Context {
         }
     }
 
+    /// Optimized physical plan of `df` and the runtime to execute it in.  `None` for remote
+    /// contexts, where the plan is executed by the scheduler.
+    pub async fn create_physical_plan(
+        &self,
+        df: &Arc<dyn DataFrame>,
+    ) -> Result<Option<(Arc<dyn ExecutionPlan>, Arc<RuntimeEnv>)>> {
+        match self {
+            Context::Local(ctx) => {
+                let plan = ctx.optimize(&df.to_logical_plan())?;
+                let plan = ctx.create_physical_plan(&plan).await?;
+                let runtime = ctx.state.lock().runtime_env.clone();
+                Ok(Some((plan, runtime)))
+            }
+            Context::Remote(_) => Ok(None),
+        }
+    }
+
+    /// execute the statements in `files`, discarding their results
     pub async fn exec_files(&mut self, files: Vec<String>) {
-        let files = files
-            .into_iter()
-            .map(|file_path| File::open(file_path).unwrap())
-            .collect::<Vec<_>>();
         for file in files {
-            let mut reader = BufReader::new(file);
-            exec_from_lines(self, &mut reader).await;
+            match fs::read_to_string(&file) {
+                Ok(sql) => {
+                    exec_script(self, &sql, None).await;
+                }
+                Err(e) => eprintln!("Unable to read {}: {}", file, e),
+            }
         }
     }
 
+    /// execute the statements in `files`, printing their results.  Returns the number of
+    /// statements that failed, counting each file that couldn't be read as one failure.
+    pub async fn exec_files_and_print(
+        &mut self,
+        files: Vec<String>,
+        print_options: &PrintOptions,
+    ) -> usize {
+        let mut failures = 0;
+        for file in files {
+            match fs::read_to_string(&file) {
+                Ok(sql) => failures += exec_script(self, &sql, Some(print_options)).await,
+                Err(e) => {
+                    eprintln!("Unable to read {}: {}", file, e);
+                    failures += 1
+                }
+            }
+        }
+        failures
+    }
+
     pub fn format_execution_config(&self) -> Option<Vec<String>> {
+        debug!("Extracting ExecutionConfig attributes");
+        ConfigSetting::ALL
+            .iter()
+            .map(|setting| {
+                let value = self.config_value(*setting)?;
+                Some(format!("{}: {}", setting.name(), value))
+            })
+            .collect()
+    }
+
+    /// Current value of `setting`, `None` for remote contexts
+    pub fn config_value(&self, setting: ConfigSetting) -> Option<String> {
         match self {
             Context::Local(ctx) => {
-                let mut config = Vec::new();
-                let cfg = ctx.state.lock().config.clone();
-                debug!("Extracting ExecutionConfig attributes");
-                config.push(format!("Target Partitions: {}", cfg.target_partitions));
-                config.push(format!("Repartition Joins: {}", cfg.repartition_joins));
-                config.push(format!(
-                    "Repartition Aggregations: {}",
-                    cfg.repartition_aggregations
-                ));
-                config.push(format!("Repartition Windows: {}", cfg.repartition_windows));
-                Some(config)
+                let state = ctx.state.lock();
+                let cfg = &state.config;
+                let value = match setting {
+                    ConfigSetting::TargetPartitions => cfg.target_partitions.to_string(),
+                    ConfigSetting::BatchSize => cfg.runtime.batch_size.to_string(),
+                    ConfigSetting::RepartitionJoins => cfg.repartition_joins.to_string(),
+                    ConfigSetting::RepartitionAggregations => {
+                        cfg.repartition_aggregations.to_string()
+                    }
+                    ConfigSetting::RepartitionWindows => cfg.repartition_windows.to_string(),
+                    // The config doesn't expose this, but the schema is only present if enabled
+                    ConfigSetting::InformationSchema => {
+                        let catalog_list = &state.catalog_list;
+                        catalog_list
+                            .catalog_names()
+                            .iter()
+                            .filter_map(|name| catalog_list.catalog(name))
+                            .any(|catalog| {
+                                catalog
+                                    .schema_names()
+                                    .iter()
+                                    .any(|s| s == INFORMATION_SCHEMA)
+                            })
+                            .to_string()
+                    }
+                };
+                Some(value)
             }
             Context::Remote(_) => None,
         }
     }
 
-    pub fn format_physical_optimizers(&self) -> Option<Vec<String>> {
+    /// Change `setting` to `value`, rebuilding the local context with the new config while
+    /// keeping its tables, functions and object stores
+    pub fn set_config(&mut self, setting: ConfigSetting, value: &str) -> Result<()> {
         match self {
             Context::Local(ctx) => {
-                let physical_opts = ctx.state.lock().config.physical_optimizers.clone();
-                debug!("Extracting Physical Optimizer Rules");
-                let opts = physical_opts
-                    .iter()
-                    .map(|opt| opt.name().to_string())
+                let config = ctx.state.lock().config.clone();
+                let config = setting.apply(config, value)?;
+                *ctx = rebuild_context(ctx, config);
+                Ok(())
+            }
+            Context::Remote(_) => Err(DataFusionError::NotImplemented(
+                "The config can only be changed for local contexts".to_string(),
+            )),
+        }
+    }
+
+    /// Tree of catalogs, their schemas and tables, and the columns of each table
+    pub fn catalog_tree(&self) -> Option<Vec<TreeNode>> {
+        match self {
+            Context::Local(ctx) => {
+                let catalog_list = ctx.state.lock().catalog_list.clone();
+                debug!("Extracting Catalogs");
+                let catalogs = catalog_list
+                    .catalog_names()
+                    .into_iter()
+                    .filter_map(|catalog_name| {
+                        let catalog = catalog_list.catalog(&catalog_name)?;
+                        let schemas = catalog
+                            .schema_names()
+                            .into_iter()
+                            .filter_map(|schema_name| {
+                                let schema = catalog.schema(&schema_name)?;
+                                let tables = schema
+                                    .table_names()
+                                    .into_iter()
+                                    .filter_map(|table_name| {
+                                        let table = schema.table(&table_name)?;
+                                        Some(table_tree(table_name, &table.schema()))
+                                    })
+                                    .collect();
+                                Some(TreeNode::branch(schema_name, tables))
+                            })
+                            .collect();
+                        Some(TreeNode::branch(catalog_name, schemas))
+                    })
                     .collect();
-                Some(opts)
+                Some(catalogs)
             }
             Context::Remote(_) => None,
         }
     }
+
+    /// Logical, optimized logical and physical plans of `sql`, each as a tree of operators
+    pub async fn plan_trees(&self, sql: &str) -> Result<Vec<TreeNode>> {
+        match self {
+            Context::Local(ctx) => {
+                let logical = ctx.create_logical_plan(sql)?;
+                let optimized = ctx.optimize(&logical)?;
+                let physical = ctx.create_physical_plan(&optimized).await?;
+                let roots = vec![
+                    ("Logical Plan", logical_plan_tree(&logical)),
+                    ("Optimized Logical Plan", logical_plan_tree(&optimized)),
+                    ("Physical Plan", physical_plan_tree(physical.as_ref())),
+                ];
+                Ok(roots
+                    .into_iter()
+                    .map(|(name, plan)| {
+                        let mut root = TreeNode::branch(name.to_string(), vec![plan]);
+                        root.expanded = true;
+                        root
+                    })
+                    .collect())
+            }
+            Context::Remote(_) => Err(DataFusionError::NotImplemented(
+                "Plans are only available for local contexts".to_string(),
+            )),
+        }
+    }
+
+    /// Name to select from each table with, see `table_reference`, and the table's schema
+    pub fn tables(&self) -> Vec<(String, SchemaRef)> {
+        match self {
+            Context::Local(ctx) => {
+                let catalog_list = ctx.state.lock().catalog_list.clone();
+                let mut tables = Vec::new();
+                for catalog_name in catalog_list.catalog_names() {
+                    let catalog = match catalog_list.catalog(&catalog_name) {
+                        Some(catalog) => catalog,
+                        None => continue,
+                    };
+                    for schema_name in catalog.schema_names() {
+                        let schema = match catalog.schema(&schema_name) {
+                            Some(schema) => schema,
+                            None => continue,
+                        };
+                        for table_name in schema.table_names() {
+                            if let Some(table) = schema.table(&table_name) {
+                                let name =
+                                    self.table_reference(&catalog_name, &schema_name, &table_name);
+                                tables.push((name, table.schema()));
+                            }
+                        }
+                    }
+                }
+                tables
+            }
+            Context::Remote(_) => Vec::new(),
+        }
+    }
+
+    /// Name to select from the table at `catalog.schema.table` with, which is unqualified if
+    /// the context resolves the bare name to it, as for tables in the default catalog and
+    /// schema.  Parts that aren't plain lower case identifiers are quoted.
+    pub fn table_reference(&self, catalog: &str, schema: &str, table: &str) -> String {
+        let bare = match self {
+            Context::Local(ctx) => resolves_bare(ctx, catalog, schema, table),
+            Context::Remote(_) => false,
+        };
+        if bare {
+            quote_identifier(table)
+        } else {
+            format!(
+                "{}.{}.{}",
+                quote_identifier(catalog),
+                quote_identifier(schema),
+                quote_identifier(table)
+            )
+        }
+    }
+
+    /// Built-in functions followed by the user defined functions registered with the context
+    pub fn function_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN_FUNCTIONS.iter().map(|f| f.to_string()).collect();
+        if let Context::Local(ctx) = self {
+            let state = ctx.state.lock();
+            let mut udfs: Vec<String> = state
+                .scalar_functions
+                .keys()
+                .chain(state.aggregate_functions.keys())
+                .cloned()
+                .collect();
+            udfs.sort();
+            names.extend(udfs);
+        }
+        names
+    }
+
+    /// Optimizer rules of the context, all enabled.  Empty for remote contexts.
+    pub fn optimizer_rules(&self) -> OptimizerRules {
+        match self {
+            Context::Local(ctx) => {
+                let physical_opts = ctx.state.lock().config.physical_optimizers.clone();
+                debug!("Extracting Physical Optimizer Rules");
+                OptimizerRules::new(default_logical_rules(), physical_opts)
+            }
+            Context::Remote(_) => OptimizerRules::new(Vec::new(), Vec::new()),
+        }
+    }
+
+    /// Rebuild the local context to apply only the enabled `rules`, keeping its tables,
+    /// functions and object stores
+    pub fn set_optimizer_rules(&mut self, rules: &OptimizerRules) -> Result<()> {
+        match self {
+            Context::Local(ctx) => {
+                let config = ctx
+                    .state
+                    .lock()
+                    .config
+                    .clone()
+                    .with_optimizer_rules(rules.enabled_logical())
+                    .with_physical_optimizer_rules(rules.enabled_physical());
+                *ctx = rebuild_context(ctx, config);
+                Ok(())
+            }
+            Context::Remote(_) => Err(DataFusionError::NotImplemented(
+                "Optimizer rules can only be changed for local contexts".to_string(),
+            )),
+        }
+    }
 }
 
-#[cfg(feature = "s3")]
-pub async fn register_s3(ctx: ExecutionContext) -> ExecutionContext {
-    use datafusion_objectstore_s3::object_store::s3::S3FileSystem;
-    ctx.register_object_store("s3", Arc::new(S3FileSystem::default().await));
-    info!("Registered S3 ObjectStore");
-    ctx
+/// Schema that lists the tables and columns of each catalog, if enabled
+const INFORMATION_SCHEMA: &str = "information_schema";
+
+/// Names of DataFusion's built-in scalar and aggregate functions
+const BUILTIN_FUNCTIONS: &[&str] = &[
+    "abs",
+    "acos",
+    "asin",
+    "atan",
+    "ceil",
+    "cos",
+    "exp",
+    "floor",
+    "ln",
+    "log",
+    "log10",
+    "log2",
+    "round",
+    "signum",
+    "sin",
+    "sqrt",
+    "tan",
+    "trunc",
+    "array",
+    "ascii",
+    "bit_length",
+    "btrim",
+    "char_length",
+    "character_length",
+    "chr",
+    "concat",
+    "concat_ws",
+    "date_part",
+    "date_trunc",
+    "digest",
+    "initcap",
+    "left",
+    "length",
+    "lower",
+    "lpad",
+    "ltrim",
+    "md5",
+    "now",
+    "nullif",
+    "octet_length",
+    "random",
+    "regexp_match",
+    "regexp_replace",
+    "repeat",
+    "replace",
+    "reverse",
+    "right",
+    "rpad",
+    "rtrim",
+    "sha224",
+    "sha256",
+    "sha384",
+    "sha512",
+    "split_part",
+    "starts_with",
+    "strpos",
+    "substr",
+    "to_hex",
+    "to_timestamp",
+    "to_timestamp_micros",
+    "to_timestamp_millis",
+    "to_timestamp_seconds",
+    "translate",
+    "trim",
+    "upper",
+    "approx_distinct",
+    "approx_median",
+    "approx_percentile_cont",
+    "array_agg",
+    "avg",
+    "corr",
+    "count",
+    "covar",
+    "covar_pop",
+    "covar_samp",
+    "max",
+    "min",
+    "stddev",
+    "stddev_pop",
+    "stddev_samp",
+    "sum",
+    "var",
+    "var_pop",
+    "var_samp",
+];
+
+/// Whether the bare name `table` resolves to the table at `catalog.schema.table`.  The
+/// config's default catalog and schema aren't exposed, so the table the context finds is
+/// compared instead.
+fn resolves_bare(ctx: &ExecutionContext, catalog: &str, schema: &str, table: &str) -> bool {
+    let provider = match ctx
+        .catalog(catalog)
+        .and_then(|c| c.schema(schema))
+        .and_then(|s| s.table(table))
+    {
+        Some(provider) => provider,
+        None => return false,
+    };
+    match ctx.table(table).map(|df| df.to_logical_plan()) {
+        Ok(LogicalPlan::TableScan(scan)) => {
+            Arc::as_ptr(&scan.source) as *const u8 == Arc::as_ptr(&provider) as *const u8
+        }
+        _ => false,
+    }
+}
+
+/// `name` as an SQL identifier, quoted unless it is a plain lower case identifier so that
+/// its case and any other characters are kept
+pub fn quote_identifier(name: &str) -> String {
+    let plain = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if plain {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
 }
 
-async fn exec_from_lines(ctx: &mut Context, reader: &mut BufReader<File>) {
-    let mut query = "".to_owned();
+/// Context with `config` that has the tables, functions and object stores of `ctx`
+fn rebuild_context(ctx: &ExecutionContext, config: ExecutionConfig) -> ExecutionContext {
+    let new_ctx = ExecutionContext::with_config(config);
+    {
+        let old = ctx.state.lock();
+        let mut new = new_ctx.state.lock();
+        new.scalar_functions = old.scalar_functions.clone();
+        new.aggregate_functions = old.aggregate_functions.clone();
+        new.object_store_registry = old.object_store_registry.clone();
+        copy_tables(old.catalog_list.as_ref(), new.catalog_list.as_ref());
+    }
+    new_ctx
+}
 
-    for line in reader.lines() {
-        match line {
-            Ok(line) if line.starts_with("--") => {
+/// Register the tables of `from` with `to`.  Catalogs missing from `to` are registered as a
+/// whole, otherwise tables are registered with the existing schemas of `to`.
+fn copy_tables(from: &dyn CatalogList, to: &dyn CatalogList) {
+    for catalog_name in from.catalog_names() {
+        let catalog = match from.catalog(&catalog_name) {
+            Some(catalog) => catalog,
+            None => continue,
+        };
+        let new_catalog = match to.catalog(&catalog_name) {
+            Some(new_catalog) => new_catalog,
+            None => {
+                to.register_catalog(catalog_name, catalog);
                 continue;
             }
-            Ok(line) => {
-                let line = line.trim_end();
-                query.push_str(line);
-                if line.ends_with(';') {
-                    match exec_and_print(ctx, query).await {
-                        Ok(_) => {}
-                        Err(err) => println!("{:?}", err),
+        };
+        for schema_name in catalog.schema_names() {
+            if schema_name == INFORMATION_SCHEMA {
+                continue;
+            }
+            let (schema, new_schema) = match (
+                catalog.schema(&schema_name),
+                new_catalog.schema(&schema_name),
+            ) {
+                (Some(schema), Some(new_schema)) => (schema, new_schema),
+                _ => {
+                    warn!("Unable to keep schema {}.{}", catalog_name, schema_name);
+                    continue;
+                }
+            };
+            for table_name in schema.table_names() {
+                if let Some(table) = schema.table(&table_name) {
+                    if let Err(e) = new_schema.register_table(table_name.clone(), table) {
+                        warn!("Unable to keep table {}: {}", table_name, e);
                     }
-                    query = "".to_owned();
-                } else {
-                    query.push('\n');
                 }
             }
-            _ => {
-                break;
-            }
         }
     }
+}
+
+fn table_tree(name: String, schema: &Schema) -> TreeNode {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            TreeNode::leaf(format!(
+                "{}: {:?}{}",
+                field.name(),
+                field.data_type(),
+                if field.is_nullable() { "" } else { " NOT NULL" }
+            ))
+        })
+        .collect();
+    TreeNode::branch(name, columns)
+}
+
+#[cfg(feature = "s3")]
+pub async fn register_s3(ctx: ExecutionContext) -> ExecutionContext {
+    use datafusion_objectstore_s3::object_store::s3::S3FileSystem;
+    ctx.register_object_store("s3", Arc::new(S3FileSystem::default().await));
+    info!("Registered S3 ObjectStore");
+    ctx
+}
 
-    // run the left over query if the last statement doesn't contain ‘;’
-    if !query.is_empty() {
-        match exec_and_print(ctx, query).await {
-            Ok(_) => {}
-            Err(err) => println!("{:?}", err),
+/// Execute the statements of the script `sql` in order, printing their results if
+/// `print_options` are given.  Returns the number of statements that failed.
+async fn exec_script(ctx: &mut Context, sql: &str, print_options: Option<&PrintOptions>) -> usize {
+    let mut failures = 0;
+    for statement in split_statements(sql) {
+        if let Err(err) = exec_and_print(ctx, statement, print_options).await {
+            eprintln!("{}", err);
+            failures += 1
         }
     }
+    failures
 }
 
-async fn exec_and_print(ctx: &mut Context, sql: String) -> Result<()> {
-    let _df = ctx.sql(&sql).await?;
+async fn exec_and_print(
+    ctx: &mut Context,
+    sql: String,
+    print_options: Option<&PrintOptions>,
+) -> Result<()> {
+    let now = Instant::now();
+    let df = ctx.sql(&sql).await?;
+    if let Some(print_options) = print_options {
+        let batches = df.collect().await?;
+        print_options.print_batches(&batches, now)?;
+    }
     Ok(())
 }
 
 // implement wrappers around the BallistaContext to support running without ballista
 
 #[cfg(feature = "ballista")]
-pub struct BallistaContext(ballista::context::BallistaContext);
+#[derive(Clone)]
+pub struct BallistaContext(Arc<ballista::context::BallistaContext>);
 #[cfg(feature = "ballista")]
 impl BallistaContext {
     pub fn try_new(host: &str, port: u16) -> Result<Self> {
@@ -194,7 +845,7 @@ impl BallistaContext {
         use ballista::prelude::BallistaConfig;
         let config: BallistaConfig =
             BallistaConfig::new().map_err(|e| DataFusionError::Execution(format!("{:?}", e)))?;
-        Ok(Self(BallistaContext::remote(host, port, &config)))
+        Ok(Self(Arc::new(BallistaContext::remote(host, port, &config))))
     }
     pub async fn sql(&mut self, sql: &str) -> Result<Arc<dyn DataFrame>> {
         self.0.sql(sql).await
@@ -202,6 +853,7 @@ impl BallistaContext {
 }
 
 #[cfg(not(feature = "ballista"))]
+#[derive(Clone)]
 pub struct BallistaContext();
 #[cfg(not(feature = "ballista"))]
 impl BallistaContext {
@@ -214,3 +866,146 @@ impl BallistaContext {
         unreachable!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::print_format::PrintFormat;
+    use datafusion::datasource::MemTable;
+    use datafusion::physical_plan::aggregates::AggregateFunction;
+    use datafusion::physical_plan::functions::BuiltinScalarFunction;
+    use std::io::Write;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_set_config_keeps_tables() {
+        let mut ctx =
+            Context::new_local(&ExecutionConfig::new().with_information_schema(true)).await;
+        ctx.sql("CREATE TABLE t AS SELECT 1 AS a").await.unwrap();
+
+        ctx.set_config(ConfigSetting::TargetPartitions, "3")
+            .unwrap();
+        assert_eq!(
+            Some("3".to_string()),
+            ctx.config_value(ConfigSetting::TargetPartitions)
+        );
+        ctx.set_config(ConfigSetting::InformationSchema, "false")
+            .unwrap();
+        assert_eq!(
+            Some("false".to_string()),
+            ctx.config_value(ConfigSetting::InformationSchema)
+        );
+
+        let batches = ctx
+            .sql("SELECT a FROM t")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(1, batches[0].num_rows());
+        assert!(ctx
+            .sql("SELECT * FROM information_schema.tables")
+            .await
+            .is_err());
+        assert!(ctx.set_config(ConfigSetting::BatchSize, "0").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_set_optimizer_rules() {
+        let mut ctx = Context::new_local(&ExecutionConfig::new()).await;
+        let sql = "SELECT a + 1 FROM (SELECT 1 + 2 AS a) t";
+        let optimized = |ctx: &Context| match ctx {
+            Context::Local(ctx) => {
+                let plan = ctx.create_logical_plan(sql).unwrap();
+                (
+                    format!("{:?}", plan),
+                    format!("{:?}", ctx.optimize(&plan).unwrap()),
+                )
+            }
+            Context::Remote(_) => unreachable!(),
+        };
+        let (plan, optimized_plan) = optimized(&ctx);
+        assert_ne!(plan, optimized_plan);
+
+        let mut rules = ctx.optimizer_rules();
+        for i in 0..rules.logical.len() {
+            rules.toggle(i);
+        }
+        ctx.set_optimizer_rules(&rules).unwrap();
+        let (plan, optimized_plan) = optimized(&ctx);
+        assert_eq!(plan, optimized_plan);
+    }
+
+    #[tokio::test]
+    async fn test_exec_files_and_print_counts_failures() {
+        let mut script = tempfile::NamedTempFile::new().unwrap();
+        script
+            .write_all(
+                b"CREATE TABLE t AS SELECT 1 AS a;\nSELECT * FROM missing;\nSELECT a\nFROM t;\n",
+            )
+            .unwrap();
+        // Files that can't be read count as a failure
+        let mut invalid = tempfile::NamedTempFile::new().unwrap();
+        invalid.write_all(&[0xff, 0xfe]).unwrap();
+        let mut ctx = Context::new_local(&ExecutionConfig::new()).await;
+        let print_options = PrintOptions {
+            format: PrintFormat::Csv,
+            quiet: true,
+        };
+        let files = vec![
+            script.path().to_str().unwrap().to_string(),
+            invalid.path().to_str().unwrap().to_string(),
+        ];
+        assert_eq!(2, ctx.exec_files_and_print(files, &print_options).await);
+    }
+
+    #[tokio::test]
+    async fn test_exec_script_ignores_quoted_semicolons() {
+        let mut ctx = Context::new_local(&ExecutionConfig::new()).await;
+        let sql = "-- comment; not a statement\nSELECT 'a;\nb' AS x;\nSELECT 1";
+        assert_eq!(0, exec_script(&mut ctx, sql, None).await);
+    }
+
+    #[tokio::test]
+    async fn test_table_reference_quotes_names() {
+        let mut ctx =
+            Context::new_local(&ExecutionConfig::new().with_information_schema(true)).await;
+        // Registered as `--data-path` does, CREATE TABLE would keep the quotes in the name
+        let batch = ctx
+            .sql("SELECT 1 AS a")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let table = MemTable::try_new(batch[0].schema(), vec![batch]).unwrap();
+        if let Context::Local(ref mut ctx) = ctx {
+            ctx.register_table("Sales-2021", Arc::new(table)).unwrap();
+        }
+        ctx.sql("CREATE TABLE orders AS SELECT 2 AS a")
+            .await
+            .unwrap();
+        let names: Vec<String> = ctx.tables().into_iter().map(|(name, _)| name).collect();
+        assert!(names.contains(&"\"Sales-2021\"".to_string()));
+        assert!(names.contains(&"orders".to_string()));
+        assert!(names.contains(&"datafusion.information_schema.tables".to_string()));
+        for name in names {
+            let sql = format!("SELECT * FROM {} LIMIT 100", name);
+            assert!(ctx.sql(&sql).await.is_ok(), "{}", sql);
+        }
+        assert_eq!("\"a\"\"b\"", quote_identifier("a\"b"));
+    }
+
+    #[test]
+    fn test_builtin_functions_exist() {
+        for name in BUILTIN_FUNCTIONS {
+            assert!(
+                BuiltinScalarFunction::from_str(name).is_ok()
+                    || AggregateFunction::from_str(name).is_ok(),
+                "{} is not a built-in function",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,201 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Registering the files found under `--data-path` as tables
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use datafusion::datasource::file_format::avro::AvroFormat;
+use datafusion::datasource::file_format::csv::CsvFormat;
+use datafusion::datasource::file_format::json::JsonFormat;
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::file_format::FileFormat;
+use datafusion::datasource::listing::ListingOptions;
+use datafusion::execution::context::ExecutionContext;
+use log::{error, info, warn};
+
+/// File formats that can be registered as tables
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DataFormat {
+    Csv,
+    Parquet,
+    Json,
+    Avro,
+}
+
+/// Extension of the files of each format, matched ignoring case
+const EXTENSIONS: &[(&str, DataFormat)] = &[
+    ("csv", DataFormat::Csv),
+    ("parquet", DataFormat::Parquet),
+    ("json", DataFormat::Json),
+    ("avro", DataFormat::Avro),
+];
+
+impl DataFormat {
+    /// Format of the file at `path` and its extension as written, which files are listed by
+    fn from_path(path: &Path) -> Option<(DataFormat, String)> {
+        let extension = path.extension()?.to_str()?;
+        EXTENSIONS
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(extension))
+            .map(|(_, format)| (*format, format!(".{}", extension)))
+    }
+
+    fn listing_options(&self, file_extension: &str, target_partitions: usize) -> ListingOptions {
+        let format: Arc<dyn FileFormat> = match self {
+            DataFormat::Csv => Arc::new(CsvFormat::default()),
+            DataFormat::Parquet => Arc::new(ParquetFormat::default()),
+            DataFormat::Json => Arc::new(JsonFormat::default()),
+            DataFormat::Avro => Arc::new(AvroFormat),
+        };
+        ListingOptions {
+            format,
+            collect_stat: false,
+            file_extension: file_extension.to_string(),
+            target_partitions,
+            table_partition_cols: vec![],
+        }
+    }
+}
+
+/// Table discovered under the data path
+#[derive(Debug, PartialEq)]
+pub struct DataTable {
+    pub name: String,
+    pub path: String,
+    pub format: DataFormat,
+    /// Extension of the table's files, including the leading '.'
+    pub extension: String,
+}
+
+/// Find the files and directories directly under `data_path` that can be registered as
+/// tables.  Files are named after their stem and directories after their whole name, with a
+/// directory's format taken from the first recognised file within it.
+pub fn discover_tables(data_path: &str) -> Vec<DataTable> {
+    let entries = match fs::read_dir(data_path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!("Unable to read data path {}: {}", data_path, e);
+            return Vec::new();
+        }
+    };
+    let mut paths: Vec<_> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let ((format, extension), name) = if path.is_dir() {
+                (directory_format(&path)?, path.file_name()?)
+            } else {
+                (DataFormat::from_path(&path)?, path.file_stem()?)
+            };
+            Some(DataTable {
+                name: name.to_str()?.to_string(),
+                path: path.to_str()?.to_string(),
+                format,
+                extension,
+            })
+        })
+        .collect()
+}
+
+fn directory_format(dir: &Path) -> Option<(DataFormat, String)> {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+    paths.sort();
+    paths.iter().find_map(|path| DataFormat::from_path(path))
+}
+
+/// Register every table found under `data_path` with `ctx`
+pub async fn register_data_path(ctx: &mut ExecutionContext, data_path: &str) {
+    let target_partitions = ctx.state.lock().config.target_partitions;
+    for table in discover_tables(data_path) {
+        if table.format == DataFormat::Avro && cfg!(not(feature = "avro")) {
+            warn!(
+                "Skipping {}, compile with feature 'avro' to read Avro files",
+                table.path
+            );
+            continue;
+        }
+        let options = table
+            .format
+            .listing_options(&table.extension, target_partitions);
+        match ctx
+            .register_listing_table(&table.name, &table.path, options, None)
+            .await
+        {
+            Ok(_) => info!(
+                "Registered {:?} table {} from {}",
+                table.format, table.name, table.path
+            ),
+            Err(e) => error!("Unable to register table {}: {}", table.name, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        fs::create_dir_all(dir.join("events.v2")).unwrap();
+        fs::create_dir_all(dir.join("empty")).unwrap();
+        fs::write(dir.join("users.csv"), "a\n1\n").unwrap();
+        fs::write(dir.join("Orders.JSON"), "").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+        fs::write(dir.join("events.v2").join("part-0.Parquet"), "").unwrap();
+
+        let tables = discover_tables(dir.to_str().unwrap());
+        let tables: Vec<(&str, DataFormat, &str)> = tables
+            .iter()
+            .map(|t| (t.name.as_str(), t.format, t.extension.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Orders", DataFormat::Json, ".JSON"),
+                ("events.v2", DataFormat::Parquet, ".Parquet"),
+                ("users", DataFormat::Csv, ".csv"),
+            ],
+            tables
+        );
+    }
+
+    #[tokio::test]
+    async fn test_register_upper_case_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("DATA.CSV"), "a\n1\n2\n").unwrap();
+        let mut ctx = ExecutionContext::new();
+        register_data_path(&mut ctx, dir.path().to_str().unwrap()).await;
+        let batches = ctx
+            .sql("SELECT * FROM \"DATA\"")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(2, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+    }
+}
@@ -15,4 +15,12 @@
 // specific language governing permissions and limitations
 // under the License.
 
+pub mod commands;
+pub mod config;
 pub mod context;
+pub mod data_path;
+pub mod optimizer;
+pub mod plan;
+pub mod query;
+pub mod script;
+pub mod view;
@@ -0,0 +1,239 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Optimizer rules of a context, which can be individually disabled
+
+use std::sync::Arc;
+
+use arrow::datatypes::DataType;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::ExecutionProps;
+use datafusion::logical_plan::{
+    DFSchemaRef, Expr, ExprSchemable, ExprSimplifiable, LogicalPlan, SimplifyInfo,
+};
+use datafusion::optimizer::common_subexpr_eliminate::CommonSubexprEliminate;
+use datafusion::optimizer::eliminate_limit::EliminateLimit;
+use datafusion::optimizer::filter_push_down::FilterPushDown;
+use datafusion::optimizer::limit_push_down::LimitPushDown;
+use datafusion::optimizer::optimizer::OptimizerRule;
+use datafusion::optimizer::projection_push_down::ProjectionPushDown;
+use datafusion::optimizer::single_distinct_to_groupby::SingleDistinctToGroupBy;
+use datafusion::optimizer::to_approx_perc::ToApproxPerc;
+use datafusion::optimizer::utils;
+use datafusion::physical_optimizer::optimizer::PhysicalOptimizerRule;
+
+pub type LogicalRule = Arc<dyn OptimizerRule + Send + Sync>;
+pub type PhysicalRule = Arc<dyn PhysicalOptimizerRule + Send + Sync>;
+
+/// Logical and physical optimizer rules of a context, in the order they are applied, and
+/// whether each is enabled
+pub struct OptimizerRules {
+    pub logical: Vec<(LogicalRule, bool)>,
+    pub physical: Vec<(PhysicalRule, bool)>,
+}
+
+impl OptimizerRules {
+    /// All of `logical` and `physical` enabled
+    pub fn new(logical: Vec<LogicalRule>, physical: Vec<PhysicalRule>) -> OptimizerRules {
+        OptimizerRules {
+            logical: logical.into_iter().map(|rule| (rule, true)).collect(),
+            physical: physical.into_iter().map(|rule| (rule, true)).collect(),
+        }
+    }
+
+    pub fn enabled_logical(&self) -> Vec<LogicalRule> {
+        enabled(&self.logical)
+    }
+
+    pub fn enabled_physical(&self) -> Vec<PhysicalRule> {
+        enabled(&self.physical)
+    }
+
+    /// Number of rules, logical and physical
+    pub fn len(&self) -> usize {
+        self.logical.len() + self.physical.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Enable or disable the rule at `index`, counting logical rules before physical ones.
+    /// Returns the rule's name and whether it is now enabled.
+    pub fn toggle(&mut self, index: usize) -> Option<(String, bool)> {
+        let (name, enabled) = if index < self.logical.len() {
+            let (rule, enabled) = &mut self.logical[index];
+            (rule.name().to_string(), enabled)
+        } else {
+            let (rule, enabled) = self.physical.get_mut(index - self.logical.len())?;
+            (rule.name().to_string(), enabled)
+        };
+        *enabled = !*enabled;
+        Some((name, *enabled))
+    }
+}
+
+fn enabled<R: Clone>(rules: &[(R, bool)]) -> Vec<R> {
+    rules
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(rule, _)| rule.clone())
+        .collect()
+}
+
+/// Logical optimizer rules applied by a default `ExecutionConfig`.  The config doesn't expose
+/// its rules so they are listed here, `test_default_logical_rules_match_datafusion` checks
+/// they optimize plans the same way DataFusion does.
+pub fn default_logical_rules() -> Vec<LogicalRule> {
+    vec![
+        Arc::new(SimplifyExpressions::default()),
+        Arc::new(CommonSubexprEliminate::new()),
+        Arc::new(EliminateLimit::new()),
+        Arc::new(ProjectionPushDown::new()),
+        Arc::new(FilterPushDown::new()),
+        Arc::new(LimitPushDown::new()),
+        Arc::new(SingleDistinctToGroupBy::new()),
+        Arc::new(ToApproxPerc::new()),
+    ]
+}
+
+/// DataFusion's `SimplifyExpressions` rule, which is private to the crate.  Evaluates constant
+/// expressions and applies boolean simplifications using the public `Expr::simplify`.
+#[derive(Default)]
+struct SimplifyExpressions {}
+
+impl OptimizerRule for SimplifyExpressions {
+    fn name(&self) -> &str {
+        "simplify_expressions"
+    }
+
+    fn optimize(
+        &self,
+        plan: &LogicalPlan,
+        execution_props: &ExecutionProps,
+    ) -> Result<LogicalPlan> {
+        // Expressions may refer to columns of any input, so all schemas are needed for typing
+        let info = SimplifyContext {
+            schemas: plan.all_schemas(),
+            props: execution_props,
+        };
+        let new_inputs = plan
+            .inputs()
+            .iter()
+            .map(|input| self.optimize(input, execution_props))
+            .collect::<Result<Vec<_>>>()?;
+        let expr = plan
+            .expressions()
+            .into_iter()
+            .map(|e| {
+                // Keep the original name so that references to the expression still resolve
+                let name = e.name(plan.schema());
+                let new_e = e.simplify(&info)?;
+                match (name, new_e.name(plan.schema())) {
+                    (Ok(name), Ok(new_name)) if name != new_name => Ok(new_e.alias(&name)),
+                    _ => Ok(new_e),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        utils::from_plan(plan, &expr, &new_inputs)
+    }
+}
+
+struct SimplifyContext<'a, 'b> {
+    schemas: Vec<&'a DFSchemaRef>,
+    props: &'b ExecutionProps,
+}
+
+impl SimplifyInfo for SimplifyContext<'_, '_> {
+    fn is_boolean_type(&self, expr: &Expr) -> Result<bool> {
+        Ok(self
+            .schemas
+            .iter()
+            .any(|schema| matches!(expr.get_type(schema), Ok(DataType::Boolean))))
+    }
+
+    fn nullable(&self, expr: &Expr) -> Result<bool> {
+        self.schemas
+            .iter()
+            .find_map(|schema| expr.nullable(schema.as_ref()).ok())
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "Could not find columns in '{}' during simplify",
+                    expr
+                ))
+            })
+    }
+
+    fn execution_props(&self) -> &ExecutionProps {
+        self.props
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::execution::context::{ExecutionConfig, ExecutionContext};
+
+    #[test]
+    fn test_toggle_optimizer_rules() {
+        let physical = ExecutionConfig::new().physical_optimizers;
+        let mut rules = OptimizerRules::new(default_logical_rules(), physical.clone());
+        let logical_len = rules.logical.len();
+        assert_eq!(logical_len + physical.len(), rules.len());
+
+        let name = rules.logical[1].0.name().to_string();
+        assert_eq!(Some((name.clone(), false)), rules.toggle(1));
+        assert_eq!(logical_len - 1, rules.enabled_logical().len());
+        assert!(rules.enabled_logical().iter().all(|r| r.name() != name));
+
+        let name = physical[0].name().to_string();
+        assert_eq!(Some((name, false)), rules.toggle(logical_len));
+        assert_eq!(physical.len() - 1, rules.enabled_physical().len());
+        assert_eq!(None, rules.toggle(rules.len()));
+
+        assert!(rules.toggle(1).unwrap().1);
+        assert_eq!(logical_len, rules.enabled_logical().len());
+    }
+
+    #[tokio::test]
+    async fn test_default_logical_rules_match_datafusion() {
+        let queries = [
+            "SELECT a + 1 * 2 AS x FROM t WHERE b = 'x' AND 1 = 1 LIMIT 5",
+            "SELECT * FROM (SELECT a, b FROM t LIMIT 0) s WHERE NOT (a > 3 OR false)",
+            "SELECT a, SUM(a + 1), SUM(a + 1) * 2 FROM t GROUP BY a",
+            "SELECT COUNT(DISTINCT a) FROM t",
+            "SELECT approx_median(a) FROM t",
+            "SELECT t1.a FROM t t1 JOIN t t2 ON t1.a = t2.a WHERE t2.b = 'y' ORDER BY t1.a LIMIT 3",
+        ];
+        let mut datafusion = ExecutionContext::new();
+        let mut ours = ExecutionContext::with_config(
+            ExecutionConfig::new().with_optimizer_rules(default_logical_rules()),
+        );
+        for ctx in [&mut datafusion, &mut ours] {
+            ctx.sql("CREATE TABLE t AS SELECT 1 AS a, 'x' AS b")
+                .await
+                .unwrap();
+        }
+        for sql in queries {
+            let optimized = |ctx: &ExecutionContext| {
+                let plan = ctx.create_logical_plan(sql).unwrap();
+                format!("{:?}", ctx.optimize(&plan).unwrap())
+            };
+            assert_eq!(optimized(&datafusion), optimized(&ours), "{}", sql);
+        }
+    }
+}
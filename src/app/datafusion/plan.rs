@@ -0,0 +1,239 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Logical and physical plans of a query as trees of operators
+
+use std::cmp;
+use std::fmt;
+use std::time::Duration;
+
+use datafusion::logical_plan::{DFSchema, LogicalPlan};
+use datafusion::physical_plan::{DisplayFormatType, ExecutionPlan};
+use tui::style::{Color, Modifier, Style};
+
+use crate::app::ui::tree::TreeNode;
+
+/// Share of the total compute time from which an operator is highlighted as dominating it
+const DOMINANT_SHARE: f64 = 0.5;
+
+/// Share of the total compute time from which an operator is highlighted as significant
+const NOTABLE_SHARE: f64 = 0.2;
+
+/// Node for `plan` and its inputs, labelled with the operator as shown by `EXPLAIN`
+pub fn logical_plan_tree(plan: &LogicalPlan) -> TreeNode {
+    let mut children = Vec::new();
+    let expressions: Vec<TreeNode> = plan
+        .expressions()
+        .iter()
+        .map(|expr| TreeNode::leaf(format!("{:?}", expr)))
+        .collect();
+    if !expressions.is_empty() {
+        children.push(TreeNode::branch(
+            format!("Expressions [{}]", expressions.len()),
+            expressions,
+        ));
+    }
+    children.push(logical_schema_tree(plan.schema()));
+    children.extend(plan.inputs().into_iter().map(logical_plan_tree));
+    operator(plan.display().to_string(), children)
+}
+
+/// Node for `plan` and its children, labelled with the operator as shown by `EXPLAIN`
+pub fn physical_plan_tree(plan: &dyn ExecutionPlan) -> TreeNode {
+    let partitions = plan.output_partitioning().partition_count();
+    let mut children = vec![
+        TreeNode::leaf(format!("Output partitions: {}", partitions)),
+        physical_schema_tree(plan),
+    ];
+    children.extend(
+        plan.children()
+            .iter()
+            .map(|child| physical_plan_tree(child.as_ref())),
+    );
+    operator(OneLine(plan).to_string(), children)
+}
+
+/// Node for an executed `plan` and its children, labelled with each operator's metrics.
+/// Operators that account for a large share of the plan's total compute time are highlighted.
+pub fn executed_plan_tree(plan: &dyn ExecutionPlan) -> TreeNode {
+    let total = total_compute(plan);
+    executed_operator_tree(plan, total)
+}
+
+fn executed_operator_tree(plan: &dyn ExecutionPlan, total_compute: usize) -> TreeNode {
+    let partitions = plan.output_partitioning().partition_count();
+    let metrics = plan.metrics().map(|metrics| {
+        metrics
+            .aggregate_by_partition()
+            .sorted_for_display()
+            .timestamps_removed()
+    });
+    let mut summary = Vec::new();
+    let mut style = Style::default();
+    let mut children = Vec::new();
+    match metrics {
+        Some(metrics) => {
+            if let Some(rows) = metrics.output_rows() {
+                summary.push(format!("rows={}", rows));
+            }
+            if let Some(compute) = metrics.elapsed_compute() {
+                let share = compute as f64 / cmp::max(total_compute, 1) as f64;
+                summary.push(format!(
+                    "compute={:?} ({:.0}%)",
+                    Duration::from_nanos(compute as u64),
+                    share * 100.0
+                ));
+                style = if share >= DOMINANT_SHARE {
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                } else if share >= NOTABLE_SHARE {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default()
+                };
+            }
+            if let Some(spills) = metrics.spill_count() {
+                summary.push(format!("spills={}", spills));
+            }
+            let all: Vec<TreeNode> = metrics
+                .iter()
+                .map(|metric| TreeNode::leaf(metric.to_string()))
+                .collect();
+            children.push(TreeNode::branch(format!("Metrics [{}]", all.len()), all));
+        }
+        None => children.push(TreeNode::leaf(String::from("No metrics recorded"))),
+    }
+    summary.push(format!("partitions={}", partitions));
+    children.push(physical_schema_tree(plan));
+    children.extend(
+        plan.children()
+            .iter()
+            .map(|child| executed_operator_tree(child.as_ref(), total_compute)),
+    );
+    let label = format!("[{}] {}", summary.join(", "), OneLine(plan));
+    let mut node = operator(label, children);
+    node.style = style;
+    node
+}
+
+/// Compute time of `plan` and all of its descendants, in nanoseconds
+fn total_compute(plan: &dyn ExecutionPlan) -> usize {
+    let compute = plan
+        .metrics()
+        .and_then(|metrics| metrics.elapsed_compute())
+        .unwrap_or(0);
+    let children: usize = plan
+        .children()
+        .iter()
+        .map(|child| total_compute(child.as_ref()))
+        .sum();
+    compute + children
+}
+
+/// Operators are expanded so the whole plan is visible, their details are collapsed
+fn operator(label: String, children: Vec<TreeNode>) -> TreeNode {
+    let mut node = TreeNode::branch(label, children);
+    node.expanded = true;
+    node
+}
+
+fn logical_schema_tree(schema: &DFSchema) -> TreeNode {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            TreeNode::leaf(format!(
+                "{}: {:?}{}",
+                field.qualified_name(),
+                field.data_type(),
+                if field.is_nullable() { "" } else { " NOT NULL" }
+            ))
+        })
+        .collect();
+    TreeNode::branch(format!("Schema [{}]", schema.fields().len()), fields)
+}
+
+fn physical_schema_tree(plan: &dyn ExecutionPlan) -> TreeNode {
+    let schema = plan.schema();
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            TreeNode::leaf(format!(
+                "{}: {:?}{}",
+                field.name(),
+                field.data_type(),
+                if field.is_nullable() { "" } else { " NOT NULL" }
+            ))
+        })
+        .collect();
+    TreeNode::branch(format!("Schema [{}]", schema.fields().len()), fields)
+}
+
+/// Displays a single operator of a physical plan, without its children
+struct OneLine<'a>(&'a dyn ExecutionPlan);
+
+impl fmt::Display for OneLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_as(DisplayFormatType::Default, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::execution::runtime_env::RuntimeEnv;
+    use datafusion::physical_plan::collect;
+    use datafusion::prelude::ExecutionContext;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_plan_trees() {
+        let ctx = ExecutionContext::new();
+        let logical = ctx.create_logical_plan("SELECT 1 + 2 AS x").unwrap();
+        let tree = logical_plan_tree(&logical);
+        assert!(tree.label.starts_with("Projection: "));
+        assert!(tree.expanded);
+        let labels: Vec<&str> = tree.children.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(
+            vec!["Expressions [1]", "Schema [1]", "EmptyRelation"],
+            labels
+        );
+        assert_eq!("x: Int64 NOT NULL", tree.children[1].children[0].label);
+
+        let physical = ctx.create_physical_plan(&logical).await.unwrap();
+        let tree = physical_plan_tree(physical.as_ref());
+        assert!(tree.label.starts_with("ProjectionExec: "));
+        assert_eq!("Output partitions: 1", tree.children[0].label);
+    }
+
+    #[tokio::test]
+    async fn test_executed_plan_tree() {
+        let ctx = ExecutionContext::new();
+        let logical = ctx.create_logical_plan("SELECT 1 + 2 AS x").unwrap();
+        let physical = ctx.create_physical_plan(&logical).await.unwrap();
+        collect(physical.clone(), Arc::new(RuntimeEnv::default()))
+            .await
+            .unwrap();
+
+        let tree = executed_plan_tree(physical.as_ref());
+        assert!(tree.label.starts_with("[rows=1, compute="));
+        assert!(tree.label.contains(", partitions=1] ProjectionExec: expr="));
+        assert!(tree.children[0].label.starts_with("Metrics ["));
+        // The projection is the only operator recording compute time
+        assert_eq!(Some(Color::Red), tree.style.fg);
+    }
+}
@@ -0,0 +1,277 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Background execution of queries
+
+use std::cmp;
+use std::sync::Arc;
+use std::time::Instant;
+
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Utc};
+use datafusion::error::Result;
+use datafusion::physical_plan::{self, ExecutionPlan};
+use futures::StreamExt;
+use log::{error, info};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+
+use crate::app::datafusion::context::{Context, QueryResultsMeta};
+
+/// Messages sent from a running query back to the `App`
+pub enum QueryEvent {
+    /// A batch was produced.  `batch` is `None` once the display row limit has been reached,
+    /// `rows` is the number of rows produced so far.
+    Batch {
+        batch: Option<RecordBatch>,
+        rows: usize,
+    },
+    /// A statement of a script, other than the last, finished
+    StatementFinished(QueryResultsMeta),
+    /// The last statement finished, along with the plan it was executed with so that its
+    /// metrics can be inspected
+    Finished(QueryResultsMeta, Option<Arc<dyn ExecutionPlan>>),
+    /// A statement failed, the remaining statements of the script are not run
+    Failed(QueryResultsMeta),
+}
+
+/// Status of the most recently submitted query
+pub enum QueryStatus {
+    Idle,
+    Running(Instant),
+    Finished,
+    Failed(String),
+    Cancelled,
+}
+
+impl QueryStatus {
+    pub fn is_running(&self) -> bool {
+        matches!(self, QueryStatus::Running(_))
+    }
+
+    pub fn format_status(&self) -> String {
+        match self {
+            QueryStatus::Idle | QueryStatus::Finished => String::new(),
+            QueryStatus::Running(started) => format!(
+                "[ Running... {:.3} seconds ] ",
+                started.elapsed().as_secs_f64()
+            ),
+            QueryStatus::Failed(_) => String::from("[ Failed ] "),
+            QueryStatus::Cancelled => String::from("[ Cancelled ] "),
+        }
+    }
+}
+
+/// A query executing on a background task
+pub struct RunningQuery {
+    /// Identifies the events sent by this query
+    pub id: usize,
+    pub sql: String,
+    pub started: Instant,
+    executed_at: DateTime<Utc>,
+    handle: JoinHandle<()>,
+}
+
+impl RunningQuery {
+    /// Abort the task, dropping the executing plan and its streams
+    pub fn cancel(self) -> QueryResultsMeta {
+        self.handle.abort();
+        info!("Cancelled query {}", self.id);
+        QueryResultsMeta {
+            query: self.sql,
+            succeeded: false,
+            error: Some(String::from("Query cancelled")),
+            rows: 0,
+            query_duration: self.started.elapsed().as_secs_f64(),
+            executed_at: self.executed_at,
+        }
+    }
+}
+
+/// Plan and execute `statements` in order on a separate tokio task so that the event loop is
+/// not blocked while they run, stopping at the first failure.  Batches of the last statement
+/// are streamed back on `tx`, tagged with `id`, as they are produced and at most `max_rows`
+/// rows are sent for display.
+pub fn spawn_query(
+    id: usize,
+    mut ctx: Context,
+    statements: Vec<String>,
+    max_rows: usize,
+    tx: UnboundedSender<(usize, QueryEvent)>,
+) -> RunningQuery {
+    let started = Instant::now();
+    let executed_at = Utc::now();
+    let sql = statements.join("\n");
+    let handle = tokio::spawn(async move {
+        let last = statements.len().saturating_sub(1);
+        for (i, query) in statements.into_iter().enumerate() {
+            let started = Instant::now();
+            let executed_at = Utc::now();
+            // Only the results of the last statement are displayed
+            let batch_tx = if i == last { Some(&tx) } else { None };
+            let event = match execute_stream(id, &mut ctx, &query, max_rows, batch_tx).await {
+                Ok((rows, plan)) => {
+                    let query_duration = started.elapsed().as_secs_f64();
+                    info!("Query finished with {} rows", rows);
+                    let meta = QueryResultsMeta {
+                        query,
+                        succeeded: true,
+                        error: None,
+                        rows,
+                        query_duration,
+                        executed_at,
+                    };
+                    if i == last {
+                        QueryEvent::Finished(meta, plan)
+                    } else {
+                        QueryEvent::StatementFinished(meta)
+                    }
+                }
+                Err(e) => failed(query, e.to_string(), started, executed_at),
+            };
+            let done = !matches!(event, QueryEvent::StatementFinished(_));
+            if tx.send((id, event)).is_err() {
+                error!("Unable to send query results, receiver dropped");
+                break;
+            }
+            if done {
+                break;
+            }
+        }
+    });
+    RunningQuery {
+        id,
+        sql,
+        started,
+        executed_at,
+        handle,
+    }
+}
+
+/// Pull batches from the query's stream, returning the total number of rows produced and the
+/// executed plan.  Batches are only sent if `tx` is given.
+async fn execute_stream(
+    id: usize,
+    ctx: &mut Context,
+    sql: &str,
+    max_rows: usize,
+    tx: Option<&UnboundedSender<(usize, QueryEvent)>>,
+) -> Result<(usize, Option<Arc<dyn ExecutionPlan>>)> {
+    let df = ctx.sql(sql).await?;
+    let (mut stream, plan) = match ctx.create_physical_plan(&df).await? {
+        Some((plan, runtime)) => (
+            physical_plan::execute_stream(plan.clone(), runtime).await?,
+            Some(plan),
+        ),
+        None => (df.execute_stream().await?, None),
+    };
+    let mut rows = 0;
+    let mut retained = 0;
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        rows += batch.num_rows();
+        let tx = match tx {
+            Some(tx) => tx,
+            None => continue,
+        };
+        let batch = if retained < max_rows {
+            let len = cmp::min(batch.num_rows(), max_rows - retained);
+            retained += len;
+            Some(batch.slice(0, len))
+        } else {
+            None
+        };
+        if tx.send((id, QueryEvent::Batch { batch, rows })).is_err() {
+            break;
+        }
+    }
+    Ok((rows, plan))
+}
+
+fn failed(
+    query: String,
+    err_msg: String,
+    started: Instant,
+    executed_at: DateTime<Utc>,
+) -> QueryEvent {
+    error!("Query failed: {}", err_msg);
+    QueryEvent::Failed(QueryResultsMeta {
+        query,
+        succeeded: false,
+        error: Some(err_msg),
+        rows: 0,
+        query_duration: started.elapsed().as_secs_f64(),
+        executed_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::datafusion::context::QueryResults;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::datasource::MemTable;
+    use datafusion::execution::context::ExecutionContext;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_max_rows_within_a_batch() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )
+        .unwrap();
+        let table =
+            MemTable::try_new(schema, vec![vec![batch.clone(), batch.clone(), batch]]).unwrap();
+        let mut ctx = ExecutionContext::new();
+        ctx.register_table("t", Arc::new(table)).unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let sql = "SELECT a FROM t".to_string();
+        spawn_query(0, Context::Local(ctx), vec![sql.clone()], 4, tx);
+        let mut results = QueryResults::new(sql);
+        let mut counts = Vec::new();
+        while let Some((_, event)) = rx.recv().await {
+            match event {
+                QueryEvent::Batch { batch, rows } => {
+                    counts.push(rows);
+                    results.batches.extend(batch);
+                }
+                QueryEvent::Finished(meta, _) => {
+                    results.meta = meta;
+                    break;
+                }
+                _ => panic!("Unexpected query event"),
+            }
+        }
+        // The row counter keeps going after the limit is reached
+        assert_eq!(vec![3, 6, 9], counts);
+        assert_eq!(
+            vec![3, 1],
+            results
+                .batches
+                .iter()
+                .map(|b| b.num_rows())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(4, results.retained_rows());
+        assert_eq!(9, results.meta.rows);
+        assert!(results.is_truncated());
+    }
+}
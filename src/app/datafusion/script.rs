@@ -0,0 +1,114 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Splitting of scripts into their individual statements
+
+/// Split `sql` into statements on semicolons outside of quotes and comments.  Each statement
+/// keeps its terminating semicolon, comments within it, and is trimmed of surrounding
+/// whitespace.  Statements consisting only of comments are dropped.
+pub fn split_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    // Whether the current statement has anything other than whitespace and comments
+    let mut has_content = false;
+    let mut chars = sql.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                has_content = true;
+                // A doubled quote is an escaped quote, which closes and reopens the literal
+                for (_, next) in chars.by_ref() {
+                    if next == c {
+                        break;
+                    }
+                }
+            }
+            '-' if matches!(chars.peek(), Some((_, '-'))) => {
+                for (_, next) in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if matches!(chars.peek(), Some((_, '*'))) => {
+                chars.next();
+                let mut previous = ' ';
+                for (_, next) in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            ';' => {
+                if has_content {
+                    statements.push(sql[start..=i].trim().to_string());
+                }
+                start = i + 1;
+                has_content = false;
+            }
+            c if !c.is_whitespace() => has_content = true,
+            _ => {}
+        }
+    }
+    if has_content {
+        statements.push(sql[start..].trim().to_string());
+    }
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements() {
+        let sql = "CREATE TABLE t AS SELECT 1 AS a;\nSELECT * FROM t;";
+        assert_eq!(
+            vec!["CREATE TABLE t AS SELECT 1 AS a;", "SELECT * FROM t;"],
+            split_statements(sql)
+        );
+        // The last statement doesn't need to be terminated
+        assert_eq!(
+            vec!["SELECT 1;", "SELECT 2"],
+            split_statements("SELECT 1; SELECT 2")
+        );
+        assert!(split_statements(" ;\n; ").is_empty());
+    }
+
+    #[test]
+    fn test_split_statements_ignores_quoted_semicolons() {
+        let sql = "SELECT 'a;b', 'it''s;' AS \"x;y\"; SELECT 2;";
+        assert_eq!(
+            vec!["SELECT 'a;b', 'it''s;' AS \"x;y\";", "SELECT 2;"],
+            split_statements(sql)
+        );
+    }
+
+    #[test]
+    fn test_split_statements_ignores_comments() {
+        let sql = "-- setup; not a statement\nSELECT 1; /* a; b */\n-- trailing;";
+        assert_eq!(
+            vec!["-- setup; not a statement\nSELECT 1;"],
+            split_statements(sql)
+        );
+        assert_eq!(
+            vec!["SELECT /* ; */ 1 -- ;\n;"],
+            split_statements("SELECT /* ; */ 1 -- ;\n;")
+        );
+    }
+}
@@ -0,0 +1,115 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Client side sorting and filtering of query results
+
+use arrow::array::{ArrayRef, BooleanArray};
+use arrow::compute::{filter_record_batch, sort_to_indices, take, SortOptions};
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+use datafusion::error::Result;
+
+/// Sort `batch` by the values of `column`, with nulls last
+pub fn sort_batch(batch: &RecordBatch, column: usize, descending: bool) -> Result<RecordBatch> {
+    let options = SortOptions {
+        descending,
+        nulls_first: false,
+    };
+    let indices = sort_to_indices(batch.column(column), Some(options), None)?;
+    let columns = batch
+        .columns()
+        .iter()
+        .map(|c| take(c.as_ref(), &indices, None))
+        .collect::<arrow::error::Result<Vec<ArrayRef>>>()?;
+    Ok(RecordBatch::try_new(batch.schema(), columns)?)
+}
+
+/// Keep the rows of `batch` where any column's display value contains `pattern`, ignoring
+/// case
+pub fn filter_batch(batch: &RecordBatch, pattern: &str) -> Result<RecordBatch> {
+    let pattern = pattern.to_lowercase();
+    let mask: BooleanArray = (0..batch.num_rows())
+        .map(|row| {
+            let matched = batch.columns().iter().any(|column| {
+                array_value_to_string(column, row)
+                    .map(|value| value.to_lowercase().contains(&pattern))
+                    .unwrap_or(false)
+            });
+            Some(matched)
+        })
+        .collect();
+    Ok(filter_record_batch(batch, &mask)?)
+}
+
+/// Concatenate `batches` into a single batch so that it can be sorted as a whole
+pub fn concat_batches(batches: &[RecordBatch]) -> Result<RecordBatch> {
+    let schema = batches[0].schema();
+    Ok(RecordBatch::concat(&schema, batches)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![Some(2), None, Some(3), Some(1)])),
+                Arc::new(StringArray::from(vec!["two", "null", "Three", "one"])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn strings(batch: &RecordBatch, column: usize) -> Vec<String> {
+        (0..batch.num_rows())
+            .map(|row| array_value_to_string(batch.column(column), row).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_batch() -> Result<()> {
+        let sorted = sort_batch(&batch(), 0, false)?;
+        assert_eq!(vec!["1", "2", "3", ""], strings(&sorted, 0));
+        assert_eq!(vec!["one", "two", "Three", "null"], strings(&sorted, 1));
+
+        let sorted = sort_batch(&batch(), 0, true)?;
+        assert_eq!(vec!["3", "2", "1", ""], strings(&sorted, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_batch() -> Result<()> {
+        let filtered = filter_batch(&batch(), "t")?;
+        assert_eq!(vec!["two", "Three"], strings(&filtered, 1));
+
+        let filtered = filter_batch(&batch(), "3")?;
+        assert_eq!(vec!["Three"], strings(&filtered, 1));
+
+        let filtered = filter_batch(&batch(), "missing")?;
+        assert_eq!(0, filtered.num_rows());
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Completion of keywords, tables, columns and functions in the SQL editor
+
+use arrow::datatypes::SchemaRef;
+use sqlparser::keywords::ALL_KEYWORDS;
+
+/// Maximum number of candidates offered at once
+const MAX_CANDIDATES: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CandidateKind {
+    Column,
+    Table,
+    Function,
+    Keyword,
+}
+
+impl CandidateKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CandidateKind::Column => "column",
+            CandidateKind::Table => "table",
+            CandidateKind::Function => "function",
+            CandidateKind::Keyword => "keyword",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Candidate {
+    pub text: String,
+    pub kind: CandidateKind,
+}
+
+/// Candidates offered for the word before the cursor, shown in a popup below it
+pub struct Completion {
+    /// Number of characters before the cursor replaced by the selected candidate
+    pub replace_len: u16,
+    pub candidates: Vec<Candidate>,
+    pub selected: usize,
+}
+
+impl Completion {
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % self.candidates.len();
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = (self.selected + self.candidates.len() - 1) % self.candidates.len();
+    }
+
+    pub fn selected(&self) -> &Candidate {
+        &self.candidates[self.selected]
+    }
+}
+
+/// Tables and functions known to the context
+pub struct CompletionSource {
+    /// Name of each table and its schema
+    pub tables: Vec<(String, SchemaRef)>,
+    pub functions: Vec<String>,
+}
+
+impl CompletionSource {
+    /// Complete `word`, the text before the cursor, in `sql`.  Columns are offered from the
+    /// tables referenced in `sql`, or only from the table `word` is qualified with.
+    pub fn complete(&self, word: &str, sql: &str) -> Option<Completion> {
+        if word.is_empty() {
+            return None;
+        }
+        let mut candidates = Vec::new();
+        if let Some((qualifier, column)) = word.rsplit_once('.') {
+            // Columns of a table, otherwise the whole word is matched against the qualified
+            // table names below
+            if let Some((_, schema)) = self.table(qualifier) {
+                for field in schema.fields() {
+                    push_match(&mut candidates, column, field.name(), CandidateKind::Column);
+                }
+                return completion(column, candidates);
+            }
+        }
+
+        let referenced = referenced_words(sql);
+        for (name, schema) in &self.tables {
+            if referenced.contains(&name.to_lowercase()) {
+                for field in schema.fields() {
+                    push_match(&mut candidates, word, field.name(), CandidateKind::Column);
+                }
+            }
+        }
+        for (name, _) in &self.tables {
+            push_match(&mut candidates, word, name, CandidateKind::Table);
+        }
+        for name in &self.functions {
+            push_match(&mut candidates, word, name, CandidateKind::Function);
+        }
+        for keyword in ALL_KEYWORDS {
+            push_match(&mut candidates, word, keyword, CandidateKind::Keyword);
+        }
+        completion(word, candidates)
+    }
+
+    fn table(&self, name: &str) -> Option<&(String, SchemaRef)> {
+        self.tables
+            .iter()
+            .find(|(table, _)| table.eq_ignore_ascii_case(name))
+    }
+}
+
+fn completion(word: &str, mut candidates: Vec<Candidate>) -> Option<Completion> {
+    if candidates.is_empty() {
+        return None;
+    }
+    candidates.truncate(MAX_CANDIDATES);
+    Some(Completion {
+        replace_len: word.chars().count() as u16,
+        candidates,
+        selected: 0,
+    })
+}
+
+/// Add `name` if it starts with `prefix`, ignoring case, and hasn't been added already
+fn push_match(candidates: &mut Vec<Candidate>, prefix: &str, name: &str, kind: CandidateKind) {
+    let matches = name.to_lowercase().starts_with(&prefix.to_lowercase());
+    if matches && !candidates.iter().any(|c| c.text == name) {
+        candidates.push(Candidate {
+            text: name.to_string(),
+            kind,
+        });
+    }
+}
+
+/// Lower cased identifiers, possibly qualified, appearing in `sql`
+fn referenced_words(sql: &str) -> Vec<String> {
+    sql.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn source() -> CompletionSource {
+        let schema = |columns: &[&str]| {
+            let fields = columns
+                .iter()
+                .map(|c| Field::new(c, DataType::Int32, true))
+                .collect();
+            Arc::new(Schema::new(fields))
+        };
+        CompletionSource {
+            tables: vec![
+                (String::from("orders"), schema(&["order_id", "customer_id"])),
+                (String::from("customers"), schema(&["customer_id", "name"])),
+                (String::from("other.public.orders"), schema(&["id"])),
+            ],
+            functions: vec![String::from("count"), String::from("concat")],
+        }
+    }
+
+    fn candidates(completion: Option<Completion>) -> Vec<(String, CandidateKind)> {
+        completion
+            .map(|c| c.candidates)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| (c.text, c.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_complete_tables_functions_and_keywords() {
+        let source = source();
+        let found = candidates(source.complete("c", "SELECT c"));
+        assert_eq!(
+            vec![
+                (String::from("customers"), CandidateKind::Table),
+                (String::from("count"), CandidateKind::Function),
+                (String::from("concat"), CandidateKind::Function),
+            ],
+            found[..3].to_vec()
+        );
+        assert!(found[3..]
+            .iter()
+            .all(|(text, kind)| *kind == CandidateKind::Keyword && text.starts_with('C')));
+        assert!(candidates(source.complete("SELE", "SELE"))
+            .contains(&(String::from("SELECT"), CandidateKind::Keyword)));
+        assert!(source.complete("", "SELECT ").is_none());
+        assert!(source.complete("zzz", "SELECT zzz").is_none());
+    }
+
+    #[test]
+    fn test_complete_columns_of_referenced_tables() {
+        let source = source();
+        assert_eq!(
+            vec![
+                (String::from("customer_id"), CandidateKind::Column),
+                (String::from("customers"), CandidateKind::Table),
+            ],
+            candidates(source.complete("cust", "SELECT cust FROM orders"))
+        );
+
+        let completion = source
+            .complete("customers.n", "SELECT customers.n")
+            .unwrap();
+        assert_eq!(1, completion.replace_len);
+        assert_eq!(
+            vec![(String::from("name"), CandidateKind::Column)],
+            candidates(Some(completion))
+        );
+    }
+
+    #[test]
+    fn test_complete_qualified_table_names() {
+        let source = source();
+        let completion = source
+            .complete("other.pu", "SELECT * FROM other.pu")
+            .unwrap();
+        assert_eq!(8, completion.replace_len);
+        assert_eq!(
+            vec![(String::from("other.public.orders"), CandidateKind::Table)],
+            candidates(Some(completion))
+        );
+    }
+}
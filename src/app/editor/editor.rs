@@ -15,13 +15,18 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use datafusion::physical_plan::ExecutionPlan;
 use log::debug;
 use std::cmp;
+use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use unicode_width::UnicodeWidthStr;
-
-use crate::app::datafusion::context::QueryResultsMeta;
+use crate::app::datafusion::context::{QueryResults, QueryResultsMeta};
+use crate::app::datafusion::query::QueryStatus;
+use crate::app::editor::completion::Completion;
+use crate::app::editor::History;
 
 /// Single line of text in SQL Editor and cursor over it
 pub struct Line {
@@ -37,6 +42,23 @@ impl Default for Line {
     }
 }
 
+/// Maximum number of edits that can be undone
+const UNDO_LIMIT: usize = 100;
+
+/// Text and cursor of the editor before an edit, restored by undo and redo
+struct Snapshot {
+    text: String,
+    cursor_row: u16,
+    cursor_column: u16,
+}
+
+/// Kind of the last edit, consecutive edits of the same kind are undone together
+#[derive(Clone, Copy, PartialEq)]
+enum EditKind {
+    Insert,
+    Delete,
+}
+
 /// All lines in SQL Editor
 pub struct Input {
     pub lines: Vec<Line>,
@@ -44,6 +66,14 @@ pub struct Input {
     pub cursor_row: u16,
     /// Current column in editor
     pub cursor_column: u16,
+    /// States to restore on undo, most recent last
+    undo_stack: Vec<Snapshot>,
+    /// States to restore on redo, most recent last
+    redo_stack: Vec<Snapshot>,
+    /// Kind of the last edit, `None` after the cursor is moved or an edit that is never grouped
+    last_edit: Option<EditKind>,
+    /// Incremented on every change to the text
+    version: usize,
 }
 
 impl Default for Input {
@@ -52,10 +82,42 @@ impl Default for Input {
             lines: Vec::<Line>::new(),
             cursor_row: 0,
             cursor_column: 0,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            last_edit: None,
+            version: 0,
         }
     }
 }
 
+impl Line {
+    fn new(text: String) -> Line {
+        Line {
+            text: io::Cursor::new(text),
+        }
+    }
+
+    /// Number of characters in the line, excluding the trailing newline
+    fn len(&self) -> u16 {
+        self.content().chars().count() as u16
+    }
+
+    /// Text of the line without the trailing newline
+    fn content(&self) -> &str {
+        let text = self.text.get_ref();
+        text.strip_suffix('\n').unwrap_or(text)
+    }
+
+    /// Byte offset of the character at `column`
+    fn byte_index(&self, column: u16) -> usize {
+        let text = self.text.get_ref();
+        text.char_indices()
+            .nth(column as usize)
+            .map(|(i, _)| i)
+            .unwrap_or_else(|| text.len())
+    }
+}
+
 impl Input {
     pub fn combine_lines(&self) -> String {
         let text: Vec<&str> = self
@@ -67,109 +129,158 @@ impl Input {
         text.join("")
     }
 
-    pub fn append_char(&mut self, c: char) {
+    /// Insert `c` at the cursor, splitting the line if `c` is a newline
+    pub fn insert_char(&mut self, c: char) {
+        // Typing is undone a word at a time, whitespace starts a new word
+        if c.is_whitespace() {
+            self.last_edit = None;
+        }
+        self.record_edit(Some(EditKind::Insert));
         if self.lines.is_empty() {
             let line = Line::default();
             self.lines.push(line)
         }
-        // self.lines[self.cursor_row as usize].text.get_mut().push(c);
+        let row = self.cursor_row as usize;
+        let index = self.lines[row].byte_index(self.cursor_column);
         match c {
             '\n' => {
-                self.lines[self.cursor_row as usize].text.get_mut().push(c);
-                debug!(
-                    "Line after appending char {:?} : {:?}",
-                    c,
-                    self.lines[self.cursor_row as usize].text.get_ref()
-                );
-                let line = Line::default();
-                self.lines.push(line);
+                // Text after the cursor, including any newline ending the line, moves down
+                let rest = self.lines[row].text.get_mut().split_off(index);
+                self.lines[row].text.get_mut().push(c);
+                self.lines.insert(row + 1, Line::new(rest));
                 self.cursor_row += 1;
                 self.cursor_column = 0;
             }
             '\t' => {
-                self.lines[self.cursor_row as usize]
-                    .text
-                    .get_mut()
-                    .push_str("    ");
+                self.lines[row].text.get_mut().insert_str(index, "    ");
                 self.cursor_column += 4
             }
             _ => {
-                self.lines[self.cursor_row as usize].text.get_mut().push(c);
+                self.lines[row].text.get_mut().insert(index, c);
                 self.cursor_column += 1;
             }
         }
         debug!(
-            "Line after appending char '{}': {}",
+            "Line after inserting char '{}': {}",
             c,
             self.lines[self.cursor_row as usize].text.get_ref()
         );
     }
 
-    pub fn pop(&mut self) -> Option<char> {
-        self.lines[self.cursor_row as usize].text.get_mut().pop()
-    }
-
     pub fn up_row(&mut self) {
+        self.last_edit = None;
         if self.cursor_row > 0 {
-            let previous_col = self.cursor_column;
             self.cursor_row -= 1;
-            let new_row_width = self.lines[self.cursor_row as usize].text.get_ref().width() as u16;
-            let new_col = cmp::min(previous_col, new_row_width);
-            self.cursor_column = new_col;
+            self.cursor_column = cmp::min(
+                self.cursor_column,
+                self.lines[self.cursor_row as usize].len(),
+            );
         }
     }
 
     pub fn down_row(&mut self) {
-        if self.lines.is_empty() {
-            return;
-        } else if self.cursor_row + 1 < self.lines.len() as u16 {
-            let previous_col = self.cursor_column;
-            let new_row_width = self.lines[self.cursor_row as usize].text.get_ref().width() as u16;
+        self.last_edit = None;
+        if self.cursor_row + 1 < self.lines.len() as u16 {
             self.cursor_row += 1;
-            let new_col = cmp::min(previous_col, new_row_width);
-            self.cursor_column = new_col;
+            self.cursor_column = cmp::min(
+                self.cursor_column,
+                self.lines[self.cursor_row as usize].len(),
+            );
         }
     }
 
     pub fn next_char(&mut self) {
-        if self.lines.is_empty()
-            || self.cursor_column
-                == self.lines[self.cursor_row as usize].text.get_ref().width() as u16
+        self.last_edit = None;
+        if !self.lines.is_empty() && self.cursor_column < self.lines[self.cursor_row as usize].len()
         {
-            return;
-        } else {
             self.cursor_column += 1
         }
     }
 
     pub fn previous_char(&mut self) {
+        self.last_edit = None;
         if self.cursor_column > 0 {
             self.cursor_column -= 1
         }
     }
 
+    /// Move the cursor to the start of the line
+    pub fn home(&mut self) {
+        self.last_edit = None;
+        self.cursor_column = 0;
+    }
+
+    /// Move the cursor to the end of the line
+    pub fn end(&mut self) {
+        self.last_edit = None;
+        if !self.lines.is_empty() {
+            self.cursor_column = self.lines[self.cursor_row as usize].len();
+        }
+    }
+
+    /// Remove the character before the cursor, joining the line onto the previous one when
+    /// the cursor is at its start
     pub fn backspace(&mut self) {
-        match self.lines[self.cursor_row as usize]
-            .text
-            .get_ref()
-            .is_empty()
-        {
-            true => {
-                self.up_row();
-                // Pop newline character
-                self.pop();
-            }
-            false => {
-                self.lines[self.cursor_row as usize]
-                    .text
-                    .get_mut()
-                    .remove((self.cursor_column - 1) as usize);
-                self.cursor_column -= 1
+        if self.cursor_column == 0 && self.cursor_row == 0 {
+            return;
+        }
+        self.record_edit(Some(EditKind::Delete));
+        if self.cursor_column > 0 {
+            self.cursor_column -= 1;
+            self.remove_char();
+        } else if self.cursor_row > 0 {
+            self.cursor_row -= 1;
+            self.cursor_column = self.lines[self.cursor_row as usize].len();
+            self.join_next_line();
+        }
+    }
+
+    /// Remove the character under the cursor, joining the next line onto this one when the
+    /// cursor is at the end of the line
+    pub fn delete(&mut self) {
+        if self.is_cursor_at_end() {
+            return;
+        }
+        self.record_edit(Some(EditKind::Delete));
+        if self.cursor_column < self.lines[self.cursor_row as usize].len() {
+            self.remove_char();
+        } else {
+            self.join_next_line();
+        }
+    }
+
+    fn remove_char(&mut self) {
+        let line = &mut self.lines[self.cursor_row as usize];
+        let index = line.byte_index(self.cursor_column);
+        line.text.get_mut().remove(index);
+    }
+
+    /// Replace the newline ending the current line with the contents of the next line
+    fn join_next_line(&mut self) {
+        let row = self.cursor_row as usize;
+        if row + 1 < self.lines.len() {
+            let next = self.lines.remove(row + 1);
+            let text = self.lines[row].text.get_mut();
+            text.pop();
+            text.push_str(next.text.get_ref());
+        }
+    }
+
+    /// Whether the cursor is after the last character of the input
+    pub fn is_cursor_at_end(&self) -> bool {
+        match self.lines.last() {
+            Some(line) => {
+                self.cursor_row as usize + 1 == self.lines.len() && self.cursor_column == line.len()
             }
+            None => true,
         }
     }
 
+    /// Remove all text, can be undone
     pub fn clear(&mut self) {
+        if !self.lines.is_empty() {
+            self.record_edit(None);
+        }
         let lines = Vec::<Line>::new();
         self.lines = lines;
         self.cursor_row = 0;
@@ -177,38 +288,613 @@ impl Input {
     }
 
     pub fn tab(&mut self) {
-        self.append_char('\t')
+        self.insert_char('\t')
+    }
+
+    /// Changes whenever the text changes, used to tell if it has been modified since it was
+    /// saved
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    /// Identifier, possibly qualified, that ends at the cursor
+    pub fn word_before_cursor(&self) -> String {
+        let line = match self.lines.get(self.cursor_row as usize) {
+            Some(line) => line,
+            None => return String::new(),
+        };
+        let before: Vec<char> = line
+            .content()
+            .chars()
+            .take(self.cursor_column as usize)
+            .collect();
+        let start = before
+            .iter()
+            .rposition(|c| !(c.is_alphanumeric() || *c == '_' || *c == '.'))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        before[start..].iter().collect()
+    }
+
+    /// Replace the `len` characters before the cursor with `text`, as a single edit
+    pub fn replace_before_cursor(&mut self, len: u16, text: &str) {
+        if self.lines.is_empty() {
+            self.lines.push(Line::default());
+        }
+        self.record_edit(None);
+        let len = cmp::min(len, self.cursor_column);
+        let line = &mut self.lines[self.cursor_row as usize];
+        let start = line.byte_index(self.cursor_column - len);
+        let end = line.byte_index(self.cursor_column);
+        line.text.get_mut().replace_range(start..end, text);
+        self.cursor_column = self.cursor_column - len + text.chars().count() as u16;
+    }
+
+    /// Replace all text with `text` as a single edit, leaving the cursor at the end
+    pub fn replace_text(&mut self, text: &str) {
+        self.record_edit(None);
+        self.set_text(&text.replace('\t', "    "));
+    }
+
+    /// Revert the last edit, or group of consecutive edits
+    pub fn undo(&mut self) {
+        if let Some(snapshot) = self.undo_stack.pop() {
+            let current = self.snapshot();
+            self.redo_stack.push(current);
+            self.restore(snapshot);
+        }
+    }
+
+    /// Reapply the last undone edit
+    pub fn redo(&mut self) {
+        if let Some(snapshot) = self.redo_stack.pop() {
+            let current = self.snapshot();
+            self.undo_stack.push(current);
+            self.restore(snapshot);
+        }
+    }
+
+    /// Save the state before an edit of `kind`, unless it continues a group of edits
+    fn record_edit(&mut self, kind: Option<EditKind>) {
+        if kind.is_none() || kind != self.last_edit {
+            let snapshot = self.snapshot();
+            self.undo_stack.push(snapshot);
+            if self.undo_stack.len() > UNDO_LIMIT {
+                self.undo_stack.remove(0);
+            }
+        }
+        self.redo_stack.clear();
+        self.last_edit = kind;
+        self.version += 1;
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            text: self.combine_lines(),
+            cursor_row: self.cursor_row,
+            cursor_column: self.cursor_column,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.set_text(&snapshot.text);
+        self.cursor_row = snapshot.cursor_row;
+        self.cursor_column = snapshot.cursor_column;
+        self.last_edit = None;
+        self.version += 1;
+    }
+
+    /// Replace all text without recording an edit, leaving the cursor at the end
+    fn set_text(&mut self, text: &str) {
+        self.lines = text
+            .split_inclusive('\n')
+            .map(|line| Line::new(line.to_string()))
+            .collect();
+        if text.ends_with('\n') {
+            self.lines.push(Line::default());
+        }
+        self.cursor_row = self.lines.len().saturating_sub(1) as u16;
+        self.cursor_column = self.lines.last().map(|line| line.len()).unwrap_or(0);
     }
 }
 
-/// The entire editor and it's state
-pub struct Editor {
+/// A named query being edited, along with the outcome of the last query run from it
+pub struct Buffer {
+    /// Identifies the buffer that a running query reports to
+    pub id: usize,
+    pub name: String,
     /// Current value of the input box
     pub input: Input,
     /// Flag if SQL statement was terminated with ';'
     pub sql_terminated: bool,
-    /// History of QueryResultMeta
-    pub history: Vec<QueryResultsMeta>,
+    /// Results from DataFusion query
+    pub query_results: Option<QueryResults>,
+    /// Status of the most recently submitted query
+    pub query_status: QueryStatus,
+    /// Statements that ran before the last statement of the most recent script
+    pub statements: Vec<QueryResultsMeta>,
+    /// Physical plan of the last query run, holding the metrics recorded while executing it
+    pub executed_plan: Option<Arc<dyn ExecutionPlan>>,
+    /// File the buffer was opened from or saved to
+    pub path: Option<PathBuf>,
+    /// Version of the input when it was last opened or saved
+    saved_version: usize,
+}
+
+impl Buffer {
+    fn new(id: usize) -> Buffer {
+        Buffer {
+            id,
+            name: format!("Query {}", id + 1),
+            input: Input::default(),
+            sql_terminated: false,
+            query_results: None,
+            query_status: QueryStatus::Idle,
+            statements: Vec::new(),
+            executed_plan: None,
+            path: None,
+            saved_version: 0,
+        }
+    }
+
+    /// Whether the text has changed since the buffer was opened or saved
+    pub fn is_dirty(&self) -> bool {
+        self.input.version() != self.saved_version
+    }
+
+    /// Write the text to `path`, which is used for the buffer's name from then on
+    pub fn save(&mut self, path: PathBuf) -> io::Result<()> {
+        let mut text = self.input.combine_lines();
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        fs::write(&path, text)?;
+        self.name = file_name(&path);
+        self.path = Some(path);
+        self.saved_version = self.input.version();
+        Ok(())
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// The entire editor and it's state
+pub struct Editor {
+    /// Open buffers, there is always at least one
+    pub buffers: Vec<Buffer>,
+    /// Index of the buffer being edited
+    pub active: usize,
+    /// Id assigned to the next buffer
+    next_buffer_id: usize,
+    /// Executed queries, persisted across sessions
+    pub history: History,
+    /// Completions offered for the word before the cursor
+    pub completion: Option<Completion>,
 }
 impl Default for Editor {
     fn default() -> Editor {
-        let mut line_lengths = Vec::new();
-        line_lengths.push(0);
-        let input = Input::default();
         Editor {
-            input,
-            history: Vec::new(),
-            sql_terminated: false,
+            buffers: vec![Buffer::new(0)],
+            active: 0,
+            next_buffer_id: 1,
+            history: History::default(),
+            completion: None,
         }
     }
 }
 
 impl Editor {
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffers[self.active]
+    }
+
+    pub fn buffer_mut(&mut self) -> &mut Buffer {
+        &mut self.buffers[self.active]
+    }
+
+    pub fn buffer_by_id_mut(&mut self, id: usize) -> Option<&mut Buffer> {
+        self.buffers.iter_mut().find(|b| b.id == id)
+    }
+
+    /// Input of the buffer being edited
+    pub fn input(&self) -> &Input {
+        &self.buffer().input
+    }
+
+    pub fn input_mut(&mut self) -> &mut Input {
+        &mut self.buffer_mut().input
+    }
+
+    /// Open an empty buffer after the current one and switch to it
+    pub fn new_buffer(&mut self) {
+        let buffer = Buffer::new(self.next_buffer_id);
+        self.next_buffer_id += 1;
+        self.active += 1;
+        self.buffers.insert(self.active, buffer);
+        self.completion = None;
+    }
+
+    /// Close the buffer being edited unless it has unsaved changes, returns whether it was
+    /// closed
+    pub fn close_buffer(&mut self) -> bool {
+        if self.buffer().is_dirty() {
+            return false;
+        }
+        self.discard_buffer();
+        true
+    }
+
+    /// Close the buffer being edited, losing any unsaved changes.  The last buffer is replaced
+    /// with an empty one.
+    pub fn discard_buffer(&mut self) {
+        self.buffers.remove(self.active);
+        if self.buffers.is_empty() {
+            self.buffers.push(Buffer::new(self.next_buffer_id));
+            self.next_buffer_id += 1;
+        }
+        self.active = cmp::min(self.active, self.buffers.len() - 1);
+        self.completion = None;
+    }
+
+    /// Whether any buffer has unsaved changes
+    pub fn is_dirty(&self) -> bool {
+        self.buffers.iter().any(|b| b.is_dirty())
+    }
+
+    pub fn next_buffer(&mut self) {
+        self.active = (self.active + 1) % self.buffers.len();
+        self.completion = None;
+    }
+
+    pub fn previous_buffer(&mut self) {
+        self.active = (self.active + self.buffers.len() - 1) % self.buffers.len();
+        self.completion = None;
+    }
+
+    /// Load the file at `path`, into the active buffer if it is empty and unmodified or
+    /// otherwise into a new buffer
+    pub fn open_file(&mut self, path: PathBuf) -> io::Result<()> {
+        let text = fs::read_to_string(&path)?;
+        let buffer = self.buffer();
+        if buffer.is_dirty() || !buffer.input.lines.is_empty() || buffer.path.is_some() {
+            self.new_buffer();
+        }
+        self.load_query(text.strip_suffix('\n').unwrap_or(&text));
+        let buffer = self.buffer_mut();
+        buffer.name = file_name(&path);
+        buffer.path = Some(path);
+        buffer.saved_version = buffer.input.version();
+        Ok(())
+    }
+
+    /// Replace the contents of the editor with `query`
+    pub fn load_query(&mut self, query: &str) {
+        self.input_mut().replace_text(query);
+        self.update_sql_terminated();
+    }
+
+    /// Flag the statement as terminated if the text ends with ';', called after each edit
+    pub fn update_sql_terminated(&mut self) {
+        let buffer = self.buffer_mut();
+        buffer.sql_terminated = buffer.input.combine_lines().trim_end().ends_with(';');
+    }
+
     pub fn get_cursor_row(&self) -> u16 {
-        self.input.cursor_row
+        self.input().cursor_row
     }
 
     pub fn get_cursor_column(&self) -> u16 {
-        self.input.cursor_column
+        self.input().cursor_column
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input_from(text: &str) -> Input {
+        let mut input = Input::default();
+        for c in text.chars() {
+            input.insert_char(c);
+        }
+        input
+    }
+
+    fn cursor(input: &Input) -> (u16, u16) {
+        (input.cursor_row, input.cursor_column)
+    }
+
+    #[test]
+    fn test_insert_mid_line() {
+        let mut input = input_from("SELECT * FROM t;");
+        for _ in 0.."* FROM t;".len() {
+            input.previous_char();
+        }
+        for c in "a, ".chars() {
+            input.insert_char(c);
+        }
+        assert_eq!("SELECT a, * FROM t;", input.combine_lines());
+        assert_eq!((0, 10), cursor(&input));
+
+        input.home();
+        input.tab();
+        assert_eq!("    SELECT a, * FROM t;", input.combine_lines());
+        assert_eq!((0, 4), cursor(&input));
+    }
+
+    #[test]
+    fn test_enter_splits_line() {
+        let mut input = input_from("SELECT 1 FROM t;");
+        for _ in 0.." FROM t;".len() {
+            input.previous_char();
+        }
+        input.insert_char('\n');
+        assert_eq!("SELECT 1\n FROM t;", input.combine_lines());
+        assert_eq!(2, input.lines.len());
+        assert_eq!((1, 0), cursor(&input));
+
+        // Splitting a line that is followed by another keeps the following line intact
+        input.up_row();
+        input.end();
+        input.previous_char();
+        input.insert_char('\n');
+        assert_eq!("SELECT \n1\n FROM t;", input.combine_lines());
+        assert_eq!((1, 0), cursor(&input));
+
+        // The cursor column counts characters, not bytes or display width
+        let mut input = input_from("SELECT 'é日' FROM t;");
+        for _ in 0.." FROM t;".len() {
+            input.previous_char();
+        }
+        assert_eq!((0, 11), cursor(&input));
+        input.insert_char('\n');
+        assert_eq!("SELECT 'é日'\n FROM t;", input.combine_lines());
+        assert_eq!((1, 0), cursor(&input));
+    }
+
+    #[test]
+    fn test_backspace() {
+        let mut input = input_from("SELECT 12;");
+        input.previous_char();
+        input.backspace();
+        assert_eq!("SELECT 1;", input.combine_lines());
+        assert_eq!((0, 8), cursor(&input));
+
+        // Backspace at the start of the input does nothing
+        input.home();
+        input.backspace();
+        assert_eq!("SELECT 1;", input.combine_lines());
+        assert_eq!((0, 0), cursor(&input));
+    }
+
+    #[test]
+    fn test_backspace_joins_lines() {
+        let mut input = input_from("SELECT 1\nFROM t\n;");
+        input.up_row();
+        input.home();
+        input.backspace();
+        assert_eq!("SELECT 1FROM t\n;", input.combine_lines());
+        assert_eq!(2, input.lines.len());
+        assert_eq!((0, 8), cursor(&input));
+
+        // Joining onto an empty line
+        let mut input = input_from("\nSELECT 1;");
+        input.home();
+        input.backspace();
+        assert_eq!("SELECT 1;", input.combine_lines());
+        assert_eq!((0, 0), cursor(&input));
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut input = input_from("SELECT 12;");
+        input.home();
+        input.delete();
+        assert_eq!("ELECT 12;", input.combine_lines());
+        assert_eq!((0, 0), cursor(&input));
+
+        // Delete at the end of the input does nothing
+        input.end();
+        input.delete();
+        assert_eq!("ELECT 12;", input.combine_lines());
+
+        let mut input = input_from("SELECT 1\nFROM t;");
+        input.up_row();
+        input.end();
+        input.delete();
+        assert_eq!("SELECT 1FROM t;", input.combine_lines());
+        assert_eq!(1, input.lines.len());
+        assert_eq!((0, 8), cursor(&input));
+    }
+
+    #[test]
+    fn test_home_end_and_navigation() {
+        let mut input = input_from("SELECT *\nFROM some_table;");
+        assert!(input.is_cursor_at_end());
+        input.home();
+        assert_eq!((1, 0), cursor(&input));
+        assert!(!input.is_cursor_at_end());
+        input.end();
+        assert_eq!((1, 16), cursor(&input));
+
+        // The column is clamped to the length of shorter lines, excluding the newline
+        input.up_row();
+        assert_eq!((0, 8), cursor(&input));
+        input.next_char();
+        assert_eq!((0, 8), cursor(&input));
+        input.down_row();
+        assert_eq!((1, 8), cursor(&input));
+        input.down_row();
+        assert_eq!((1, 8), cursor(&input));
+    }
+
+    #[test]
+    fn test_multibyte_characters() {
+        let mut input = input_from("SELECT 'ü';");
+        input.previous_char();
+        input.previous_char();
+        input.backspace();
+        input.insert_char('é');
+        assert_eq!("SELECT 'é';", input.combine_lines());
+    }
+
+    #[test]
+    fn test_undo_grouped_typing() {
+        let mut input = input_from("SELECT a, b");
+        input.undo();
+        assert_eq!("SELECT a,", input.combine_lines());
+        input.undo();
+        assert_eq!("SELECT", input.combine_lines());
+        input.undo();
+        assert_eq!("", input.combine_lines());
+        input.undo();
+        assert_eq!("", input.combine_lines());
+
+        input.redo();
+        input.redo();
+        assert_eq!("SELECT a,", input.combine_lines());
+        assert_eq!((0, 9), cursor(&input));
+
+        // Moving the cursor ends the group
+        input.insert_char('x');
+        input.previous_char();
+        input.insert_char('y');
+        assert_eq!("SELECT a,yx", input.combine_lines());
+        input.undo();
+        assert_eq!("SELECT a,x", input.combine_lines());
+        assert_eq!((0, 9), cursor(&input));
+
+        // A new edit discards the undone edits
+        input.insert_char('z');
+        input.redo();
+        assert_eq!("SELECT a,zx", input.combine_lines());
+    }
+
+    #[test]
+    fn test_undo_deletions() {
+        let mut input = input_from("SELECT 1\nFROM t;");
+        input.backspace();
+        input.backspace();
+        input.home();
+        input.backspace();
+        assert_eq!("SELECT 1FROM ", input.combine_lines());
+        input.undo();
+        assert_eq!("SELECT 1\nFROM ", input.combine_lines());
+        assert_eq!((1, 0), cursor(&input));
+
+        input.undo();
+        assert_eq!("SELECT 1\nFROM t;", input.combine_lines());
+        assert_eq!((1, 7), cursor(&input));
+
+        input.up_row();
+        input.home();
+        input.delete();
+        input.delete();
+        input.undo();
+        assert_eq!("SELECT 1\nFROM t;", input.combine_lines());
+        input.redo();
+        assert_eq!("LECT 1\nFROM t;", input.combine_lines());
+    }
+
+    #[test]
+    fn test_undo_clear() {
+        let mut input = input_from("SELECT 1;\n");
+        input.clear();
+        assert!(input.lines.is_empty());
+        input.undo();
+        assert_eq!("SELECT 1;\n", input.combine_lines());
+        assert_eq!((1, 0), cursor(&input));
+        input.redo();
+        assert_eq!("", input.combine_lines());
+
+        input.replace_text("SELECT 2;");
+        input.undo();
+        assert_eq!("", input.combine_lines());
+        input.undo();
+        assert_eq!("SELECT 1;\n", input.combine_lines());
+    }
+
+    #[test]
+    fn test_buffers() {
+        let mut editor = Editor::default();
+        editor.load_query("SELECT 1;");
+        editor.new_buffer();
+        editor.load_query("SELECT 2");
+        assert_eq!(vec!["Query 1", "Query 2"], buffer_names(&editor));
+        assert!(!editor.buffer().sql_terminated);
+
+        editor.previous_buffer();
+        assert_eq!("SELECT 1;", editor.input().combine_lines());
+        assert!(editor.buffer().sql_terminated);
+        editor.new_buffer();
+        assert_eq!(vec!["Query 1", "Query 3", "Query 2"], buffer_names(&editor));
+        assert_eq!(1, editor.active);
+
+        editor.discard_buffer();
+        assert_eq!("SELECT 2", editor.input().combine_lines());
+        editor.discard_buffer();
+        assert_eq!("SELECT 1;", editor.input().combine_lines());
+        editor.next_buffer();
+        assert_eq!(0, editor.active);
+
+        // Closing the last buffer leaves an empty one
+        editor.discard_buffer();
+        assert_eq!(vec!["Query 4"], buffer_names(&editor));
+        assert!(editor.input().lines.is_empty());
+    }
+
+    #[test]
+    fn test_close_buffer_keeps_unsaved_changes() {
+        let mut editor = Editor::default();
+        assert!(!editor.is_dirty());
+        editor.new_buffer();
+        editor.load_query("SELECT 1;");
+        assert!(editor.is_dirty());
+        assert!(!editor.close_buffer());
+        assert_eq!(2, editor.buffers.len());
+        assert_eq!("SELECT 1;", editor.input().combine_lines());
+
+        // Unmodified buffers close straight away
+        editor.previous_buffer();
+        assert!(editor.close_buffer());
+        assert_eq!(vec!["Query 2"], buffer_names(&editor));
+        editor.discard_buffer();
+        assert!(!editor.is_dirty());
+    }
+
+    fn buffer_names(editor: &Editor) -> Vec<&str> {
+        editor.buffers.iter().map(|b| b.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_open_and_save_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let path = dir.join("daily.sql");
+        fs::write(&path, "SELECT 1;\n").unwrap();
+
+        let mut editor = Editor::default();
+        assert!(!editor.buffer().is_dirty());
+        editor.open_file(path.clone()).unwrap();
+        assert_eq!(1, editor.buffers.len());
+        assert_eq!("daily.sql", editor.buffer().name);
+        assert_eq!("SELECT 1;", editor.input().combine_lines());
+        assert!(!editor.buffer().is_dirty());
+
+        editor.input_mut().backspace();
+        assert!(editor.buffer().is_dirty());
+        let copy = dir.join("copy.sql");
+        editor.buffer_mut().save(copy.clone()).unwrap();
+        assert!(!editor.buffer().is_dirty());
+        assert_eq!("copy.sql", editor.buffer().name);
+        assert_eq!("SELECT 1\n", fs::read_to_string(&copy).unwrap());
+
+        // The buffer now holds a file so opening another uses a new buffer
+        editor.open_file(path).unwrap();
+        assert_eq!(vec!["copy.sql", "daily.sql"], buffer_names(&editor));
     }
 }
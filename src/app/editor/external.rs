@@ -0,0 +1,72 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Editing the query in an external editor
+
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::process::Command;
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` are set
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Command from `$VISUAL` or `$EDITOR`, split on whitespace so that arguments such as
+/// `code --wait` can be included
+fn editor_command() -> Vec<String> {
+    let command = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_default();
+    let words: Vec<String> = command.split_whitespace().map(String::from).collect();
+    if words.is_empty() {
+        vec![DEFAULT_EDITOR.to_string()]
+    } else {
+        words
+    }
+}
+
+/// Write `text` to a temporary file, wait for the user's editor to exit and return the
+/// edited contents.  The terminal must be released before calling this.
+pub fn edit_externally(text: &str) -> io::Result<String> {
+    // Created exclusively with an unpredictable name, and removed when dropped
+    let mut file = tempfile::Builder::new()
+        .prefix("dft-")
+        .suffix(".sql")
+        .tempfile()?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+
+    let command = editor_command();
+    let status = Command::new(&command[0])
+        .args(&command[1..])
+        .arg(file.path())
+        .status();
+    let edited = match status {
+        // Editors may replace the file rather than write to it, so read it back by path
+        Ok(status) if status.success() => fs::read_to_string(file.path()),
+        Ok(status) => Err(io::Error::other(format!(
+            "{} exited with {}",
+            command[0], status
+        ))),
+        Err(e) => Err(io::Error::new(
+            e.kind(),
+            format!("Unable to run {}: {}", command[0], e),
+        )),
+    };
+    // Editors usually end the file with a newline that wasn't in the query
+    edited.map(|text| text.trim_end_matches('\n').to_string())
+}
@@ -0,0 +1,274 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Query history persisted across sessions
+
+use std::cmp;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use log::{debug, error, warn};
+
+use crate::app::datafusion::context::QueryResultsMeta;
+
+/// Executed queries, most recent last.  When a path is set every entry is appended to it
+/// as a line of JSON.  Only the last `max_entries` are kept in memory, the file is rewritten
+/// with just those once it holds more than twice as many lines so that pushing a query
+/// doesn't rewrite the whole file every time.
+pub struct History {
+    entries: Vec<QueryResultsMeta>,
+    path: Option<PathBuf>,
+    max_entries: usize,
+    /// Number of lines in the history file
+    file_entries: usize,
+}
+
+impl Default for History {
+    fn default() -> History {
+        History {
+            entries: Vec::new(),
+            path: None,
+            max_entries: usize::MAX,
+            file_entries: 0,
+        }
+    }
+}
+
+impl History {
+    /// `history.jsonl` in the platform's data directory, e.g. `~/.local/share/datafusion-tui`
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("datafusion-tui").join("history.jsonl"))
+    }
+
+    /// Load the last `max_entries` entries of the history stored at `path`.  Lines that can't
+    /// be parsed are skipped so that a corrupt file doesn't lose the rest of the history.
+    pub fn load(path: PathBuf, max_entries: usize) -> History {
+        let mut entries = Vec::new();
+        let mut file_entries = 0;
+        if let Ok(file) = File::open(&path) {
+            for (i, line) in BufReader::new(file).lines().enumerate() {
+                file_entries = i + 1;
+                match line.map(|l| serde_json::from_str::<QueryResultsMeta>(&l)) {
+                    Ok(Ok(meta)) => entries.push(meta),
+                    Ok(Err(e)) => warn!("Skipping history entry {}: {}", i + 1, e),
+                    Err(e) => {
+                        error!("Unable to read history from {:?}: {}", path, e);
+                        break;
+                    }
+                }
+            }
+        }
+        debug!("Loaded {} history entries from {:?}", entries.len(), path);
+        let mut history = History {
+            entries,
+            path: Some(path),
+            max_entries,
+            file_entries,
+        };
+        history.trim();
+        history
+    }
+
+    pub fn entries(&self) -> &[QueryResultsMeta] {
+        &self.entries
+    }
+
+    pub fn last(&self) -> Option<&QueryResultsMeta> {
+        self.entries.last()
+    }
+
+    /// Record `meta` and append it to the history file
+    pub fn push(&mut self, meta: QueryResultsMeta) {
+        match self.append(&meta) {
+            Ok(()) => self.file_entries += 1,
+            Err(e) => error!("Unable to save query history: {}", e),
+        }
+        self.entries.push(meta);
+        self.trim();
+    }
+
+    fn append(&self, meta: &QueryResultsMeta) -> std::io::Result<()> {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(meta)?)
+    }
+
+    /// Drop the oldest entries beyond `max_entries`, and rewrite the history file once it
+    /// holds more than twice as many lines
+    fn trim(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.max_entries);
+        self.entries.drain(..excess);
+        if self.file_entries > self.max_entries.saturating_mul(2) {
+            match self.rewrite() {
+                Ok(()) => self.file_entries = self.entries.len(),
+                Err(e) => error!("Unable to rotate query history: {}", e),
+            }
+        }
+    }
+
+    fn rewrite(&self) -> std::io::Result<()> {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+        // Written to a temporary file first so the history survives a failed write
+        let tmp = path.with_extension("jsonl.tmp");
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        for meta in &self.entries {
+            writeln!(writer, "{}", serde_json::to_string(meta)?)?;
+        }
+        writer.flush()?;
+        fs::rename(tmp, path)
+    }
+}
+
+/// Selection and search over the history listed in the Query History tab
+#[derive(Default)]
+pub struct HistoryBrowser {
+    /// Index of the selected entry among the matching ones
+    pub selected: usize,
+    /// Only entries containing this text, ignoring case, are listed
+    pub search: String,
+}
+
+impl HistoryBrowser {
+    /// Indices of the entries matching the search, most recent first
+    pub fn matches(&self, history: &History) -> Vec<usize> {
+        let search = self.search.to_lowercase();
+        (0..history.entries.len())
+            .rev()
+            .filter(|i| history.entries[*i].query.to_lowercase().contains(&search))
+            .collect()
+    }
+
+    pub fn selected_entry<'a>(&self, history: &'a History) -> Option<&'a QueryResultsMeta> {
+        self.matches(history)
+            .get(self.selected)
+            .map(|i| &history.entries[*i])
+    }
+
+    pub fn next(&mut self, history: &History) {
+        let len = self.matches(history).len();
+        self.selected = cmp::min(self.selected + 1, len.saturating_sub(1));
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn set_search(&mut self, search: String) {
+        self.search = search;
+        self.selected = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn meta(query: &str) -> QueryResultsMeta {
+        QueryResultsMeta {
+            query: query.to_string(),
+            succeeded: true,
+            error: None,
+            rows: 1,
+            query_duration: 0.5,
+            executed_at: Utc::now(),
+        }
+    }
+
+    fn queries(history: &History) -> Vec<&str> {
+        history.entries().iter().map(|m| m.query.as_str()).collect()
+    }
+
+    #[test]
+    fn test_history_persisted_and_rotated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("history.jsonl");
+        let lines = |path: &PathBuf| fs::read_to_string(path).unwrap().lines().count();
+
+        let mut history = History::load(path.clone(), 2);
+        assert!(history.entries().is_empty());
+        for i in 1..=4 {
+            history.push(meta(&format!("SELECT {};", i)));
+        }
+        assert_eq!(vec!["SELECT 3;", "SELECT 4;"], queries(&history));
+        // The file is only rewritten once it holds more than twice `max_entries`
+        assert_eq!(4, lines(&path));
+        history.push(meta("SELECT 5;"));
+        assert_eq!(2, lines(&path));
+        history.push(meta("SELECT 3;"));
+        assert_eq!(vec!["SELECT 5;", "SELECT 3;"], queries(&history));
+        assert_eq!(3, lines(&path));
+
+        let reloaded = History::load(path.clone(), 2);
+        assert_eq!(vec!["SELECT 5;", "SELECT 3;"], queries(&reloaded));
+        assert_eq!(
+            history.last().unwrap().executed_at,
+            reloaded.last().unwrap().executed_at
+        );
+
+        // Lowering the limit keeps the most recent entries, unparseable lines are skipped
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "not json").unwrap();
+        let reloaded = History::load(path.clone(), 1);
+        assert_eq!(vec!["SELECT 3;"], queries(&reloaded));
+        assert_eq!(1, lines(&path));
+    }
+
+    #[test]
+    fn test_history_browser() {
+        let mut history = History::default();
+        for query in ["SELECT a FROM t;", "select b from u;", "SHOW TABLES;"] {
+            history.push(meta(query));
+        }
+        let mut browser = HistoryBrowser::default();
+        assert_eq!(vec![2, 1, 0], browser.matches(&history));
+        assert_eq!(
+            "SHOW TABLES;",
+            browser.selected_entry(&history).unwrap().query
+        );
+
+        browser.next(&history);
+        browser.next(&history);
+        browser.next(&history);
+        assert_eq!(
+            "SELECT a FROM t;",
+            browser.selected_entry(&history).unwrap().query
+        );
+
+        browser.set_search(String::from("Select"));
+        assert_eq!(vec![1, 0], browser.matches(&history));
+        assert_eq!(
+            "select b from u;",
+            browser.selected_entry(&history).unwrap().query
+        );
+        browser.previous();
+        assert_eq!(0, browser.selected);
+
+        browser.set_search(String::from("missing"));
+        assert!(browser.selected_entry(&history).is_none());
+    }
+}
@@ -15,6 +15,10 @@
 // specific language governing permissions and limitations
 // under the License.
 
+pub mod completion;
 mod editor;
+pub mod external;
+mod history;
 
-pub use editor::Editor;
+pub use editor::{Buffer, Editor};
+pub use history::{History, HistoryBrowser};
@@ -0,0 +1,49 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io;
+
+use crate::app::{App, AppReturn, InputMode};
+use crate::events::Key;
+
+/// Navigation of the catalog tree, returns `None` if the key was not handled
+pub fn catalog_handler(app: &mut App, key: Key) -> Option<io::Result<AppReturn>> {
+    match key {
+        Key::Down => app.catalog.next(),
+        Key::Up => app.catalog.previous(),
+        Key::Right => app.catalog.expand(),
+        Key::Left => app.catalog.collapse(),
+        Key::Enter | Key::Char(' ') => app.catalog.toggle(),
+        Key::Char('r') => app.refresh_catalog(),
+        Key::Char('s') => select_from_table(app),
+        _ => return None,
+    };
+    Some(Ok(AppReturn::Continue))
+}
+
+/// Load a query selecting from the selected table into the editor
+fn select_from_table(app: &mut App) {
+    let labels = app.catalog.selected_labels();
+    if labels.len() < 3 {
+        return;
+    }
+    let table = app.context.table_reference(labels[0], labels[1], labels[2]);
+    let query = format!("SELECT * FROM {} LIMIT 100;", table);
+    app.editor.load_query(&query);
+    app.tabs.index = 0;
+    app.input_mode = InputMode::Editing;
+}
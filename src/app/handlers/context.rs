@@ -0,0 +1,61 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io;
+
+use crate::app::datafusion::config::ConfigSetting;
+use crate::app::{App, AppReturn, Prompt, PromptKind};
+use crate::events::Key;
+
+/// Selection and editing of the context's settings and optimizer rules, returns `None` if
+/// the key was not handled
+pub fn context_handler(app: &mut App, key: Key) -> Option<io::Result<AppReturn>> {
+    let settings = ConfigSetting::ALL.len();
+    match key {
+        Key::Down => {
+            if app.context_selected + 1 < settings + app.optimizer_rules.len() {
+                app.context_selected += 1
+            }
+        }
+        Key::Up => app.context_selected = app.context_selected.saturating_sub(1),
+        Key::Enter | Key::Char(' ') if app.context_selected < settings => edit_setting(app),
+        Key::Enter | Key::Char(' ') => app.toggle_optimizer_rule(app.context_selected - settings),
+        _ => return None,
+    };
+    Some(Ok(AppReturn::Continue))
+}
+
+/// Toggle the selected setting if it is a flag, otherwise prompt for its new value
+fn edit_setting(app: &mut App) {
+    let setting = ConfigSetting::ALL[app.context_selected];
+    let value = match app.context.config_value(setting) {
+        Some(value) => value,
+        None => {
+            app.set_status(
+                String::from("The config can only be changed for local contexts"),
+                true,
+            );
+            return;
+        }
+    };
+    if setting.is_flag() {
+        let enabled = value == "true";
+        app.set_config(setting, (!enabled).to_string())
+    } else {
+        app.prompt = Some(Prompt::with_input(PromptKind::ConfigValue(setting), value))
+    }
+}
@@ -17,36 +17,50 @@
 
 use log::debug;
 use std::io;
-use std::time::Instant;
 
-use crate::app::datafusion::context::{QueryResults, QueryResultsMeta};
-use crate::app::ui::Scroll;
+use crate::app::datafusion::commands::Command;
+use crate::app::editor::completion::{Completion, CompletionSource};
 use crate::app::{App, AppReturn, InputMode};
 use crate::events::Key;
 
 pub async fn edit_mode_handler(app: &mut App, key: Key) -> io::Result<AppReturn> {
     debug!(
         "{} Entered, current row / col: {} / {}",
-        key, app.editor.input.cursor_row, app.editor.input.cursor_column
+        key,
+        app.editor.input().cursor_row,
+        app.editor.input().cursor_column
     );
+    if app.editor.completion.is_some() && completion_handler(app, key) {
+        return Ok(AppReturn::Continue);
+    }
     match key {
-        Key::Enter => enter_handler(app).await,
-        Key::Char(c) => match c {
-            ';' => {
-                app.editor.input.append_char(c);
-                app.editor.sql_terminated = true;
-            }
-            _ => {
-                app.editor.input.append_char(c);
-            }
-        },
-        Key::Left => app.editor.input.previous_char(),
-        Key::Right => app.editor.input.next_char(),
-        Key::Up => app.editor.input.up_row(),
-        Key::Down => app.editor.input.down_row(),
-        Key::Tab => app.editor.input.tab(),
+        Key::Enter => return Ok(enter_handler(app)),
+        Key::Char(c) => {
+            app.editor.input_mut().insert_char(c);
+            app.editor.update_sql_terminated();
+        }
+        Key::Left => app.editor.input_mut().previous_char(),
+        Key::Right => app.editor.input_mut().next_char(),
+        Key::Up => app.editor.input_mut().up_row(),
+        Key::Down => app.editor.input_mut().down_row(),
+        Key::Home => app.editor.input_mut().home(),
+        Key::End => app.editor.input_mut().end(),
+        Key::Tab => complete(app),
         Key::Backspace => {
-            app.editor.input.backspace();
+            app.editor.input_mut().backspace();
+            app.editor.update_sql_terminated();
+        }
+        Key::Delete => {
+            app.editor.input_mut().delete();
+            app.editor.update_sql_terminated();
+        }
+        Key::Ctrl('z') => {
+            app.editor.input_mut().undo();
+            app.editor.update_sql_terminated();
+        }
+        Key::Ctrl('y') => {
+            app.editor.input_mut().redo();
+            app.editor.update_sql_terminated();
         }
         Key::Esc => {
             app.input_mode = InputMode::Normal;
@@ -56,48 +70,76 @@ pub async fn edit_mode_handler(app: &mut App, key: Key) -> io::Result<AppReturn>
     Ok(AppReturn::Continue)
 }
 
-async fn enter_handler(app: &mut App) {
-    match app.editor.sql_terminated {
-        false => {
-            app.editor.input.append_char('\n');
-        }
+fn enter_handler(app: &mut App) -> AppReturn {
+    // Enter only executes the query from the end of the input, elsewhere it splits the line
+    if !app.editor.input().is_cursor_at_end() {
+        app.editor.input_mut().insert_char('\n');
+        return AppReturn::Continue;
+    }
+    let sql: String = app.editor.input().combine_lines();
+    // Meta-commands don't need a closing ';'
+    if let Some(command) = Command::parse(&sql) {
+        app.editor.buffer_mut().sql_terminated = false;
+        return match command {
+            Ok(command) => app.run_command(command),
+            Err(e) => {
+                app.set_status(e, true);
+                AppReturn::Continue
+            }
+        };
+    }
+    match app.editor.buffer().sql_terminated {
+        false => app.editor.input_mut().insert_char('\n'),
         true => {
-            let sql: String = app.editor.input.combine_lines();
-            app.editor.sql_terminated = false;
+            app.editor.buffer_mut().sql_terminated = false;
+            app.run_query(sql);
+        }
+    }
+    AppReturn::Continue
+}
 
-            let now = Instant::now();
-            let df = app.context.sql(&sql).await;
-            match df {
-                Ok(df) => {
-                    let batches = df.collect().await.unwrap();
-                    let query_duration = now.elapsed().as_secs_f64();
-                    let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
-                    let query_meta = QueryResultsMeta {
-                        query: sql,
-                        succeeded: true,
-                        error: None,
-                        rows,
-                        query_duration,
-                    };
-                    app.editor.history.push(query_meta.clone());
-                    app.query_results = Some(QueryResults {
-                        batches,
-                        meta: query_meta,
-                        scroll: Scroll { x: 0, y: 0 },
-                    });
-                }
-                Err(e) => {
-                    let err_msg = format!("{}", e);
-                    let query_meta = QueryResultsMeta {
-                        query: sql,
-                        succeeded: false,
-                        error: Some(err_msg),
-                        rows: 0,
-                        query_duration: 0.0,
-                    };
-                    app.editor.history.push(query_meta)
-                }
-            }
+/// Complete the word before the cursor, inserting a tab if there is no word
+fn complete(app: &mut App) {
+    let word = app.editor.input().word_before_cursor();
+    if word.is_empty() {
+        app.editor.input_mut().tab();
+        return;
+    }
+    let source = CompletionSource {
+        tables: app.context.tables(),
+        functions: app.context.function_names(),
+    };
+    match source.complete(&word, &app.editor.input().combine_lines()) {
+        Some(completion) if completion.candidates.len() == 1 => apply_completion(app, completion),
+        Some(completion) => app.editor.completion = Some(completion),
+        None => debug!("No completions for '{}'", word),
+    }
+}
+
+/// Navigation of the completion popup, returns `false` if the key closed the popup without
+/// being handled
+fn completion_handler(app: &mut App, key: Key) -> bool {
+    let completion = app.editor.completion.as_mut().unwrap();
+    match key {
+        Key::Down | Key::Tab => completion.next(),
+        Key::Up => completion.previous(),
+        Key::Enter => {
+            let completion = app.editor.completion.take().unwrap();
+            apply_completion(app, completion)
+        }
+        Key::Esc => app.editor.completion = None,
+        _ => {
+            app.editor.completion = None;
+            return false;
         }
     }
+    true
+}
+
+fn apply_completion(app: &mut App, completion: Completion) {
+    let candidate = completion.selected();
+    app.editor
+        .input_mut()
+        .replace_before_cursor(completion.replace_len, &candidate.text);
+    app.editor.update_sql_terminated();
 }
@@ -0,0 +1,54 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io;
+
+use crate::app::{App, AppReturn};
+use crate::events::Key;
+
+pub fn file_picker_handler(app: &mut App, key: Key) -> io::Result<AppReturn> {
+    let picker = match app.file_picker {
+        Some(ref mut picker) => picker,
+        None => return Ok(AppReturn::Continue),
+    };
+    let result = match key {
+        Key::Down => {
+            picker.next();
+            Ok(None)
+        }
+        Key::Up => {
+            picker.previous();
+            Ok(None)
+        }
+        Key::Enter | Key::Right => picker.enter(),
+        Key::Backspace | Key::Left => picker.up().map(|_| None),
+        Key::Esc => {
+            app.file_picker = None;
+            Ok(None)
+        }
+        _ => Ok(None),
+    };
+    match result {
+        Ok(Some(path)) => {
+            app.file_picker = None;
+            app.open_file(path)
+        }
+        Ok(None) => {}
+        Err(e) => app.set_status(format!("Unable to list files: {}", e), true),
+    }
+    Ok(AppReturn::Continue)
+}
@@ -0,0 +1,61 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use log::info;
+use std::io;
+
+use crate::app::{App, AppReturn, InputMode, Prompt, PromptKind};
+use crate::events::Key;
+
+/// Navigation of the query history, returns `None` if the key was not handled
+pub fn history_handler(app: &mut App, key: Key) -> Option<io::Result<AppReturn>> {
+    match key {
+        Key::Down => app.history_browser.next(&app.editor.history),
+        Key::Up => app.history_browser.previous(),
+        Key::Char('/') => {
+            let search = app.history_browser.search.clone();
+            app.prompt = Some(Prompt::with_input(PromptKind::HistorySearch, search))
+        }
+        Key::Enter => {
+            if load_selected_query(app).is_some() {
+                app.input_mode = InputMode::Editing;
+            }
+        }
+        Key::Char('r') if app.is_query_running() => {
+            info!("Wait for the running query to finish before re-running a query")
+        }
+        Key::Char('r') => {
+            if let Some(query) = load_selected_query(app) {
+                app.run_query(query);
+            }
+        }
+        _ => return None,
+    };
+    Some(Ok(AppReturn::Continue))
+}
+
+/// Load the selected query into the editor and switch to the SQL Editor tab
+fn load_selected_query(app: &mut App) -> Option<String> {
+    let query = app
+        .history_browser
+        .selected_entry(&app.editor.history)?
+        .query
+        .clone();
+    app.editor.load_query(&query);
+    app.tabs.index = 0;
+    Some(query)
+}
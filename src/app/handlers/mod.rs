@@ -15,8 +15,15 @@
 // specific language governing permissions and limitations
 // under the License.
 
+pub mod catalog;
+pub mod context;
 pub mod edit;
+pub mod file_picker;
+pub mod history;
 pub mod normal;
+pub mod plan;
+pub mod prompt;
+pub mod results;
 
 use std::io;
 
@@ -24,8 +31,30 @@ use crate::app::{App, AppReturn, InputMode};
 use crate::events::Key;
 
 pub async fn key_event_handler<'a>(app: &mut App, key: Key) -> io::Result<AppReturn> {
+    if key == Key::Ctrl('c') && app.is_query_running() {
+        app.cancel_query();
+        return Ok(AppReturn::Continue);
+    }
+    if app.file_picker.is_some() {
+        return file_picker::file_picker_handler(app, key);
+    }
+    if app.prompt.is_some() {
+        return prompt::prompt_handler(app, key);
+    }
+    // Files can be opened and saved from either mode
+    match key {
+        Key::Ctrl('o') => {
+            app.open_file_picker();
+            return Ok(AppReturn::Continue);
+        }
+        Key::Ctrl('s') => {
+            app.save_buffer();
+            return Ok(AppReturn::Continue);
+        }
+        _ => {}
+    }
     match app.input_mode {
-        InputMode::Normal => normal::normal_mode_handler(app, key),
+        InputMode::Normal => normal::normal_mode_handler(app, key).await,
         InputMode::Editing => edit::edit_mode_handler(app, key).await,
     }
 }
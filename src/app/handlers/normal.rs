@@ -17,7 +17,12 @@
 
 use std::io;
 
-use crate::app::{App, AppReturn, InputMode};
+use crate::app::handlers::catalog::catalog_handler;
+use crate::app::handlers::context::context_handler;
+use crate::app::handlers::history::history_handler;
+use crate::app::handlers::plan::plan_handler;
+use crate::app::handlers::results::results_handler;
+use crate::app::{App, AppReturn, InputMode, Prompt, PromptKind};
 use crate::events::Key;
 
 pub enum NormalModeAction {
@@ -25,18 +30,68 @@ pub enum NormalModeAction {
     Exit,
 }
 
-pub fn normal_mode_handler(app: &mut App, key: Key) -> io::Result<AppReturn> {
+pub async fn normal_mode_handler(app: &mut App, key: Key) -> io::Result<AppReturn> {
+    let handled = match app.tabs.index {
+        0 => results_handler(app, key),
+        1 => history_handler(app, key),
+        2 => context_handler(app, key),
+        4 => catalog_handler(app, key),
+        5 => plan_handler(app, key).await,
+        _ => None,
+    };
+    if let Some(result) = handled {
+        return result;
+    }
     let result = match key {
         Key::Char('c') => {
-            app.editor.input.clear();
+            app.editor.input_mut().clear();
+            app.input_mode = InputMode::Editing;
+            Ok(AppReturn::Continue)
+        }
+        // Undo and redo act on the editor, which is only shown on the first tab
+        Key::Char('u') if app.tabs.index == 0 => {
+            app.editor.input_mut().undo();
+            app.editor.update_sql_terminated();
+            Ok(AppReturn::Continue)
+        }
+        Key::Ctrl('r') if app.tabs.index == 0 => {
+            app.editor.input_mut().redo();
+            app.editor.update_sql_terminated();
+            Ok(AppReturn::Continue)
+        }
+        Key::Char('n') => {
+            app.editor.new_buffer();
+            app.tabs.index = 0;
             app.input_mode = InputMode::Editing;
             Ok(AppReturn::Continue)
         }
+        Key::Char('w') => {
+            app.close_buffer();
+            Ok(AppReturn::Continue)
+        }
+        Key::Char('R') => {
+            let name = app.editor.buffer().name.clone();
+            app.prompt = Some(Prompt::with_input(PromptKind::RenameBuffer, name));
+            Ok(AppReturn::Continue)
+        }
+        Key::Char('S') => {
+            app.prompt_save_path();
+            Ok(AppReturn::Continue)
+        }
+        Key::Char(']') => {
+            app.editor.next_buffer();
+            Ok(AppReturn::Continue)
+        }
+        Key::Char('[') => {
+            app.editor.previous_buffer();
+            Ok(AppReturn::Continue)
+        }
+        Key::Char('E') => Ok(AppReturn::EditExternally),
         Key::Char('e') => {
             app.input_mode = InputMode::Editing;
             Ok(AppReturn::Continue)
         }
-        Key::Char('q') => Ok(AppReturn::Exit),
+        Key::Char('q') => Ok(app.quit()),
         Key::Char(c) => {
             if c.is_ascii_digit() {
                 let input_idx = c.to_digit(10).unwrap() as usize;
@@ -44,51 +99,17 @@ pub fn normal_mode_handler(app: &mut App, key: Key) -> io::Result<AppReturn> {
                     app.tabs.index = input_idx
                 } else {
                 };
+                if app.tabs.index == 4 {
+                    app.refresh_catalog()
+                }
+                if app.tabs.index == 5 {
+                    app.refresh_plan().await
+                }
                 Ok(AppReturn::Continue)
             } else {
                 Ok(AppReturn::Continue)
             }
         }
-        Key::Down => {
-            match app.query_results {
-                Some(ref mut results) => results.scroll.x += 1,
-                None => {}
-            };
-            Ok(AppReturn::Continue)
-        }
-        Key::Up => {
-            match app.query_results {
-                Some(ref mut results) => {
-                    let new_x = match results.scroll.x {
-                        0 => 0,
-                        n => n - 1,
-                    };
-                    results.scroll.x = new_x
-                }
-                None => {}
-            };
-            Ok(AppReturn::Continue)
-        }
-        Key::Right => {
-            match app.query_results {
-                Some(ref mut results) => results.scroll.y += 3,
-                None => {}
-            };
-            Ok(AppReturn::Continue)
-        }
-        Key::Left => {
-            match app.query_results {
-                Some(ref mut results) => {
-                    let new_y = match results.scroll.y {
-                        0 | 1 | 2 => 0,
-                        n => n - 3,
-                    };
-                    results.scroll.y = new_y
-                }
-                None => {}
-            };
-            Ok(AppReturn::Continue)
-        }
         _ => Ok(AppReturn::Continue),
     };
     result
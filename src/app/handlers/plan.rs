@@ -0,0 +1,35 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io;
+
+use crate::app::{App, AppReturn};
+use crate::events::Key;
+
+/// Navigation of the plan trees, returns `None` if the key was not handled
+pub async fn plan_handler(app: &mut App, key: Key) -> Option<io::Result<AppReturn>> {
+    match key {
+        Key::Down => app.plan.next(),
+        Key::Up => app.plan.previous(),
+        Key::Right => app.plan.expand(),
+        Key::Left => app.plan.collapse(),
+        Key::Enter | Key::Char(' ') => app.plan.toggle(),
+        Key::Char('r') => app.refresh_plan().await,
+        _ => return None,
+    };
+    Some(Ok(AppReturn::Continue))
+}
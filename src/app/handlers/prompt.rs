@@ -0,0 +1,148 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use log::error;
+use std::io;
+use std::path::PathBuf;
+
+use crate::app::{App, AppReturn, Prompt, PromptKind};
+use crate::cli::print_format::ExportFormat;
+use crate::events::Key;
+
+pub fn prompt_handler(app: &mut App, key: Key) -> io::Result<AppReturn> {
+    let prompt = match app.prompt {
+        Some(ref mut prompt) => prompt,
+        None => return Ok(AppReturn::Continue),
+    };
+    match key {
+        Key::Char(c) => prompt.input.push(c),
+        Key::Backspace => {
+            prompt.input.pop();
+        }
+        Key::Esc => {
+            if let PromptKind::HistorySearch = prompt.kind {
+                app.history_browser.set_search(String::new())
+            }
+            app.prompt = None;
+            return Ok(AppReturn::Continue);
+        }
+        Key::Enter => {
+            let prompt = app.prompt.take().unwrap();
+            return Ok(submit(app, prompt.kind, prompt.input));
+        }
+        _ => {}
+    };
+    // The history search is applied while typing rather than once submitted
+    if let Some(Prompt {
+        kind: PromptKind::HistorySearch,
+        ref input,
+    }) = app.prompt
+    {
+        app.history_browser.set_search(input.clone());
+    }
+    Ok(AppReturn::Continue)
+}
+
+fn submit(app: &mut App, kind: PromptKind, input: String) -> AppReturn {
+    match kind {
+        PromptKind::HistorySearch => app.history_browser.set_search(input),
+        PromptKind::RenameBuffer => {
+            if !input.trim().is_empty() {
+                app.editor.buffer_mut().name = input.trim().to_string();
+            }
+        }
+        PromptKind::SavePath => {
+            if !input.trim().is_empty() {
+                app.save_buffer_as(PathBuf::from(input.trim()));
+            }
+        }
+        PromptKind::ConfigValue(setting) => app.set_config(setting, input),
+        PromptKind::ConfirmClose if is_yes(&input) => app.editor.discard_buffer(),
+        PromptKind::ConfirmQuit if is_yes(&input) => return AppReturn::Exit,
+        PromptKind::ConfirmClose | PromptKind::ConfirmQuit => {}
+        PromptKind::Filter => {
+            if let Some(ref mut results) = app.editor.buffer_mut().query_results {
+                let filter = if input.is_empty() { None } else { Some(input) };
+                if let Err(e) = results.set_filter(filter) {
+                    error!("Unable to filter results: {}", e);
+                }
+            }
+        }
+        PromptKind::ExportPath => {
+            if input.is_empty() {
+                return AppReturn::Continue;
+            }
+            let format = ExportFormat::from_extension(&input)
+                .unwrap_or_else(|| app.export_format.clone())
+                .name()
+                .to_string();
+            app.prompt = Some(Prompt::with_input(
+                PromptKind::ExportFormat { path: input },
+                format,
+            ));
+        }
+        PromptKind::ExportFormat { path } => match input.parse::<ExportFormat>() {
+            Ok(format) => export_results(app, &path, format),
+            Err(e) => app.set_status(format!("Invalid export format: {}", e), true),
+        },
+    }
+    AppReturn::Continue
+}
+
+/// Confirmations default to no, only an explicit yes goes ahead
+fn is_yes(input: &str) -> bool {
+    matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+fn export_results(app: &mut App, path: &str, format: ExportFormat) {
+    let buffer = app.editor.buffer();
+    if buffer.query_status.is_running() {
+        app.set_status(
+            "Wait for the query to finish before exporting".to_string(),
+            true,
+        );
+        return;
+    }
+    let results = match buffer.query_results {
+        Some(ref results) => results,
+        None => return,
+    };
+    // Only the rows shown are exported, which may not be all of the query's rows
+    let exported = results.retained_rows();
+    let mut reasons = Vec::new();
+    if results.is_truncated() {
+        reasons.push("display row limit");
+    }
+    if results.filter.is_some() {
+        reasons.push("filtered");
+    }
+    let (text, is_error) = match format.write_batches(&results.batches, path) {
+        Ok(()) if reasons.is_empty() => (format!("Exported {} rows to {}", exported, path), false),
+        Ok(()) => (
+            format!(
+                "Exported {} of {} rows to {} ({})",
+                exported,
+                results.meta.rows,
+                path,
+                reasons.join(", ")
+            ),
+            false,
+        ),
+        Err(e) => (format!("Unable to export results: {}", e), true),
+    };
+    app.set_status(text, is_error)
+}
@@ -0,0 +1,70 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use log::{error, info};
+use std::io;
+
+use crate::app::datafusion::context::QueryResults;
+use crate::app::{App, AppReturn, Prompt, PromptKind};
+use crate::events::Key;
+
+/// Navigation of the query results table, returns `None` if the key was not handled
+pub fn results_handler(app: &mut App, key: Key) -> Option<io::Result<AppReturn>> {
+    let running = app.is_query_running();
+    let results = app.editor.buffer_mut().query_results.as_mut()?;
+    if results.inspector.is_some() {
+        inspector_handler(results, key);
+        return Some(Ok(AppReturn::Continue));
+    }
+    match key {
+        Key::Char('s') | Key::Char('/') if running => {
+            info!("Wait for the query to finish before sorting or filtering results")
+        }
+        Key::Char('s') => {
+            if let Err(e) = results.toggle_sort() {
+                error!("Unable to sort results: {}", e);
+            }
+        }
+        Key::Char('/') => app.prompt = Some(Prompt::new(PromptKind::Filter)),
+        Key::Char('x') if running => info!("Wait for the query to finish before exporting"),
+        Key::Char('x') => app.prompt = Some(Prompt::new(PromptKind::ExportPath)),
+        Key::Down => results.move_down(1),
+        Key::Up => results.move_up(1),
+        Key::Right => results.move_right(),
+        Key::Left => results.move_left(),
+        Key::PageDown => results.move_down(results.page_size),
+        Key::PageUp => results.move_up(results.page_size),
+        Key::Home => results.move_to_top(),
+        Key::End => results.move_to_bottom(),
+        Key::Enter => results.inspect(),
+        _ => return None,
+    };
+    Some(Ok(AppReturn::Continue))
+}
+
+fn inspector_handler(results: &mut QueryResults, key: Key) {
+    let inspector = results.inspector.as_mut().unwrap();
+    match (key, inspector.tree.as_mut()) {
+        (Key::Esc, _) | (Key::Enter, None) => results.inspector = None,
+        (Key::Down, Some(tree)) => tree.next(),
+        (Key::Up, Some(tree)) => tree.previous(),
+        (Key::Right, Some(tree)) => tree.expand(),
+        (Key::Left, Some(tree)) => tree.collapse(),
+        (Key::Enter, Some(tree)) | (Key::Char(' '), Some(tree)) => tree.toggle(),
+        _ => {}
+    }
+}
@@ -21,4 +21,4 @@ pub mod editor;
 pub mod handlers;
 pub mod ui;
 
-pub use app::{App, AppReturn, InputMode};
+pub use app::{App, AppReturn, InputMode, Prompt, PromptKind, StatusMessage};
@@ -0,0 +1,89 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Popup listing completions below the editor cursor
+
+use std::cmp;
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::App;
+
+/// Maximum number of candidates visible at once
+const MAX_VISIBLE: u16 = 8;
+/// Width of the longest candidate kind label, "function"
+const LABEL_WIDTH: u16 = 8;
+
+/// Draw the completion popup, if open, under the word being completed in `editor_area`
+pub fn draw_completion<B: Backend>(f: &mut Frame<B>, app: &App, editor_area: Rect) {
+    let completion = match app.editor.completion {
+        Some(ref completion) => completion,
+        None => return,
+    };
+    let text_width = completion
+        .candidates
+        .iter()
+        .map(|c| c.text.width())
+        .max()
+        .unwrap_or(0);
+    let items: Vec<ListItem> = completion
+        .candidates
+        .iter()
+        .map(|c| {
+            ListItem::new(Spans::from(vec![
+                Span::raw(format!("{:width$} ", c.text, width = text_width)),
+                Span::styled(c.kind.label(), Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    let size = f.size();
+    let width = cmp::min(text_width as u16 + 1 + LABEL_WIDTH + 2, size.width);
+    let height = cmp::min(completion.candidates.len() as u16, MAX_VISIBLE) + 2;
+    let word_start = app
+        .editor
+        .get_cursor_column()
+        .saturating_sub(completion.replace_len);
+    let cursor_y = editor_area.y + 1 + app.editor.get_cursor_row();
+    let x = cmp::min(
+        editor_area.x + 1 + word_start,
+        size.width.saturating_sub(width),
+    );
+    // Below the cursor, or above it if there isn't room
+    let y = if cursor_y + 1 + height <= size.height {
+        cursor_y + 1
+    } else {
+        cursor_y.saturating_sub(height)
+    };
+    let area = Rect::new(x, y, width, height);
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    state.select(Some(completion.selected));
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
@@ -0,0 +1,191 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Popup for choosing a `.sql` file to open in the editor
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tui::{
+    backend::Backend,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+use crate::app::ui::inspector::centered_rect;
+
+/// File or directory listed by the `FilePicker`
+pub struct FileEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// Directories and `.sql` files under `dir`, directories first
+pub struct FilePicker {
+    pub dir: PathBuf,
+    pub entries: Vec<FileEntry>,
+    pub selected: usize,
+}
+
+impl FilePicker {
+    pub fn new(dir: PathBuf) -> io::Result<FilePicker> {
+        let entries = read_entries(&dir)?;
+        Ok(FilePicker {
+            dir,
+            entries,
+            selected: 0,
+        })
+    }
+
+    pub fn next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Enter the selected directory, or return the selected file
+    pub fn enter(&mut self) -> io::Result<Option<PathBuf>> {
+        let entry = match self.entries.get(self.selected) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        if !entry.is_dir {
+            return Ok(Some(entry.path.clone()));
+        }
+        let dir = entry.path.clone();
+        self.change_dir(dir)?;
+        Ok(None)
+    }
+
+    /// Move to the parent directory
+    pub fn up(&mut self) -> io::Result<()> {
+        match self.dir.parent() {
+            Some(parent) => self.change_dir(parent.to_path_buf()),
+            None => Ok(()),
+        }
+    }
+
+    fn change_dir(&mut self, dir: PathBuf) -> io::Result<()> {
+        // `..` entries are resolved so the title shows where the picker actually is
+        let dir = dir.canonicalize()?;
+        self.entries = read_entries(&dir)?;
+        self.dir = dir;
+        self.selected = 0;
+        Ok(())
+    }
+}
+
+fn read_entries(dir: &Path) -> io::Result<Vec<FileEntry>> {
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => continue,
+        };
+        if path.is_dir() {
+            if !name.starts_with('.') {
+                dirs.push(FileEntry {
+                    name: format!("{}/", name),
+                    path,
+                    is_dir: true,
+                });
+            }
+        } else if is_sql_file(&path) {
+            files.push(FileEntry {
+                name,
+                path,
+                is_dir: false,
+            });
+        }
+    }
+    dirs.sort_by(|a, b| a.name.cmp(&b.name));
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut entries = Vec::new();
+    if dir.parent().is_some() {
+        entries.push(FileEntry {
+            name: String::from("../"),
+            path: dir.join(".."),
+            is_dir: true,
+        });
+    }
+    entries.extend(dirs);
+    entries.extend(files);
+    Ok(entries)
+}
+
+fn is_sql_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.eq_ignore_ascii_case("sql"))
+        .unwrap_or(false)
+}
+
+pub fn draw_file_picker<B: Backend>(f: &mut Frame<B>, picker: &FilePicker) {
+    let area = centered_rect(60, 60, f.size());
+    f.render_widget(Clear, area);
+    let items: Vec<ListItem> = picker
+        .entries
+        .iter()
+        .map(|entry| ListItem::new(entry.name.clone()))
+        .collect();
+    let title = format!("Open {} (Esc to close)", picker.dir.display());
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    state.select(Some(picker.selected));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_directories_then_sql_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        fs::create_dir_all(dir.join("reports")).unwrap();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        for file in ["b.sql", "a.SQL", "notes.txt"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let mut picker = FilePicker::new(dir.clone()).unwrap();
+        let names: Vec<&str> = picker.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(vec!["../", "reports/", "a.SQL", "b.sql"], names);
+
+        picker.next();
+        assert_eq!(None, picker.enter().unwrap());
+        assert!(picker.dir.ends_with("reports"));
+        assert_eq!(1, picker.entries.len());
+
+        picker.up().unwrap();
+        picker.next();
+        picker.next();
+        let path = picker.enter().unwrap().unwrap();
+        assert!(path.ends_with("a.SQL"));
+    }
+}
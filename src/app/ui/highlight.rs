@@ -0,0 +1,278 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! SQL syntax highlighting of the editor text
+
+use sqlparser::dialect::GenericDialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::{Token, Tokenizer, Whitespace};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans, Text};
+
+fn keyword_style() -> Style {
+    Style::default()
+        .fg(Color::Blue)
+        .add_modifier(Modifier::BOLD)
+}
+
+fn string_style() -> Style {
+    Style::default().fg(Color::Green)
+}
+
+fn number_style() -> Style {
+    Style::default().fg(Color::Magenta)
+}
+
+fn comment_style() -> Style {
+    Style::default()
+        .fg(Color::DarkGray)
+        .add_modifier(Modifier::ITALIC)
+}
+
+fn operator_style() -> Style {
+    Style::default().fg(Color::Cyan)
+}
+
+/// Split `sql` into lines of styled spans.  SQL that can't be tokenized, such as a string
+/// that hasn't been closed yet, is highlighted up to the failing token and the rest is
+/// styled as a string or comment if that is what it starts, or left unstyled otherwise.
+pub fn highlight_sql(sql: &str) -> Text<'static> {
+    let mut text = StyledText::default();
+    let (tokens, rest) = tokenize(sql);
+    let mut remaining = &sql[..sql.len() - rest.len()];
+    for token in tokens {
+        let len = match source_len(&token, remaining) {
+            Some(len) => len,
+            // The token doesn't match the text it came from, give up on highlighting
+            None => break,
+        };
+        let (source, tail) = remaining.split_at(len);
+        text.push(source, token_style(&token));
+        remaining = tail;
+    }
+    text.push(remaining, Style::default());
+
+    let rest_style = if rest.starts_with('\'') {
+        string_style()
+    } else if rest.starts_with("/*") {
+        comment_style()
+    } else {
+        Style::default()
+    };
+    text.push(rest, rest_style);
+    text.finish()
+}
+
+/// Tokens of `sql`, or of the text before the token that failed to tokenize along with the
+/// remaining text
+fn tokenize(sql: &str) -> (Vec<Token>, &str) {
+    let dialect = GenericDialect {};
+    match Tokenizer::new(&dialect, sql).tokenize() {
+        Ok(tokens) => (tokens, ""),
+        Err(e) => {
+            let offset = byte_offset(sql, e.line, e.col);
+            let (valid, rest) = sql.split_at(offset);
+            match Tokenizer::new(&dialect, valid).tokenize() {
+                Ok(tokens) => (tokens, rest),
+                Err(_) => (Vec::new(), sql),
+            }
+        }
+    }
+}
+
+/// Byte offset of the 1-based `line` and `col` reported by the tokenizer
+fn byte_offset(sql: &str, line: u64, col: u64) -> usize {
+    let mut offset = 0;
+    for (i, text) in sql.split_inclusive('\n').enumerate() {
+        if i as u64 + 1 == line {
+            return offset
+                + text
+                    .char_indices()
+                    .nth(col.saturating_sub(1) as usize)
+                    .map(|(i, _)| i)
+                    .unwrap_or_else(|| text.len());
+        }
+        offset += text.len();
+    }
+    sql.len()
+}
+
+/// Length in bytes of the text at the start of `source` that produced `token`
+fn source_len(token: &Token, source: &str) -> Option<usize> {
+    match token {
+        Token::SingleQuotedString(_) => quoted_len(source, 0),
+        Token::NationalStringLiteral(_) | Token::HexStringLiteral(_) => quoted_len(source, 1),
+        _ => {
+            let text = token.to_string();
+            if source.starts_with(&text) {
+                Some(text.len())
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Length of a single quoted string following a `prefix_len` byte prefix, the token's value
+/// has `''` escapes replaced so can't be compared with the source
+fn quoted_len(source: &str, prefix_len: usize) -> Option<usize> {
+    let mut chars = source.get(prefix_len..)?.char_indices().peekable();
+    if chars.next()?.1 != '\'' {
+        return None;
+    }
+    while let Some((i, c)) = chars.next() {
+        if c == '\'' {
+            match chars.peek() {
+                Some((_, '\'')) => {
+                    chars.next();
+                }
+                _ => return Some(prefix_len + i + 1),
+            }
+        }
+    }
+    None
+}
+
+fn token_style(token: &Token) -> Style {
+    match token {
+        Token::Word(w) if w.keyword != Keyword::NoKeyword && w.quote_style.is_none() => {
+            keyword_style()
+        }
+        Token::Whitespace(Whitespace::SingleLineComment { .. })
+        | Token::Whitespace(Whitespace::MultiLineComment(_)) => comment_style(),
+        Token::Word(_) | Token::Whitespace(_) => Style::default(),
+        Token::Number(_, _) => number_style(),
+        Token::SingleQuotedString(_)
+        | Token::NationalStringLiteral(_)
+        | Token::HexStringLiteral(_) => string_style(),
+        Token::Comma
+        | Token::Period
+        | Token::SemiColon
+        | Token::LParen
+        | Token::RParen
+        | Token::Char(_)
+        | Token::EOF => Style::default(),
+        _ => operator_style(),
+    }
+}
+
+/// Lines of spans being built up from styled fragments of text
+#[derive(Default)]
+struct StyledText {
+    lines: Vec<Spans<'static>>,
+    current: Vec<Span<'static>>,
+}
+
+impl StyledText {
+    fn push(&mut self, text: &str, style: Style) {
+        let mut parts = text.split('\n');
+        if let Some(first) = parts.next() {
+            self.push_span(first, style);
+        }
+        for part in parts {
+            let line = std::mem::take(&mut self.current);
+            self.lines.push(Spans::from(line));
+            self.push_span(part, style);
+        }
+    }
+
+    fn push_span(&mut self, text: &str, style: Style) {
+        if !text.is_empty() {
+            self.current.push(Span::styled(text.to_string(), style));
+        }
+    }
+
+    fn finish(mut self) -> Text<'static> {
+        self.lines.push(Spans::from(self.current));
+        Text::from(self.lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Content and style of each span, per line
+    fn spans(sql: &str) -> Vec<Vec<(String, Style)>> {
+        highlight_sql(sql)
+            .lines
+            .into_iter()
+            .map(|line| {
+                line.0
+                    .into_iter()
+                    .map(|span| (span.content.to_string(), span.style))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn styled(text: &str, style: Style) -> (String, Style) {
+        (text.to_string(), style)
+    }
+
+    #[test]
+    fn test_highlight_tokens() {
+        let plain = Style::default();
+        assert_eq!(
+            vec![
+                vec![
+                    styled("SELECT", keyword_style()),
+                    styled(" ", plain),
+                    styled("a", plain),
+                    styled(" ", plain),
+                    styled("+", operator_style()),
+                    styled(" ", plain),
+                    styled("1", number_style()),
+                    styled(",", plain),
+                    styled(" ", plain),
+                    styled("'it''s'", string_style()),
+                    styled(" ", plain),
+                    styled("-- note", comment_style()),
+                ],
+                vec![
+                    styled("FROM", keyword_style()),
+                    styled(" ", plain),
+                    styled("\"select\"", plain),
+                    styled(";", plain),
+                ],
+            ],
+            spans("SELECT a + 1, 'it''s' -- note\nFROM \"select\";")
+        );
+    }
+
+    #[test]
+    fn test_highlight_incomplete_sql() {
+        let plain = Style::default();
+        assert_eq!(
+            vec![
+                vec![styled("SELECT", keyword_style()), styled(" ", plain)],
+                vec![styled("'abc", string_style())],
+                vec![styled(" FROM", string_style())],
+            ],
+            spans("SELECT \n'abc\n FROM")
+        );
+        assert_eq!(
+            vec![vec![
+                styled("1", number_style()),
+                styled(" ", plain),
+                styled("/* open", comment_style()),
+            ]],
+            spans("1 /* open")
+        );
+        assert_eq!(vec![Vec::<(String, Style)>::new()], spans(""));
+    }
+}
@@ -0,0 +1,174 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Popup showing the full value of the selected results cell
+
+use arrow::array::{as_list_array, as_map_array, as_struct_array, Array, ArrayRef};
+use arrow::datatypes::DataType;
+use arrow::util::display::array_value_to_string;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::datafusion::context::QueryResults;
+use crate::app::ui::tree::{draw_tree, Tree, TreeNode};
+
+/// Details of a single cell, shown in a popup over the results table
+pub struct CellInspector {
+    pub column: String,
+    pub data_type: DataType,
+    pub value: String,
+    /// Expandable view of List, Struct and Map values
+    pub tree: Option<Tree>,
+}
+
+impl CellInspector {
+    /// Inspect the cell at the current selection of `results`
+    pub fn new(results: &QueryResults) -> Option<CellInspector> {
+        let (batch, row) = results.locate_row(results.selection.y)?;
+        let col = results.selection.x;
+        if col >= batch.num_columns() {
+            return None;
+        }
+        let schema = batch.schema();
+        let field = schema.field(col);
+        let array = batch.column(col);
+        let value = array_value_to_string(array, row).unwrap_or_else(|e| e.to_string());
+        let tree = match field.data_type() {
+            DataType::List(_) | DataType::Struct(_) | DataType::Map(_, _) => {
+                let mut root = value_tree(field.name().clone(), array, row);
+                root.expanded = true;
+                Some(Tree::new(vec![root]))
+            }
+            _ => None,
+        };
+        Some(CellInspector {
+            column: field.name().clone(),
+            data_type: field.data_type().clone(),
+            value,
+            tree,
+        })
+    }
+}
+
+/// Build a tree node for the value at `row` of `array`, recursing into nested types
+fn value_tree(name: String, array: &ArrayRef, row: usize) -> TreeNode {
+    if array.is_null(row) {
+        return TreeNode::leaf(format!("{}: NULL", name));
+    }
+    match array.data_type() {
+        DataType::Struct(fields) => {
+            let struct_array = as_struct_array(array);
+            let children = fields
+                .iter()
+                .zip(struct_array.columns())
+                .map(|(field, column)| value_tree(field.name().clone(), column, row))
+                .collect();
+            TreeNode::branch(format!("{}: {{{}}}", name, fields.len()), children)
+        }
+        DataType::List(_) => {
+            let values = as_list_array(array).value(row);
+            let children = (0..values.len())
+                .map(|i| value_tree(format!("[{}]", i), &values, i))
+                .collect();
+            TreeNode::branch(format!("{}: [{}]", name, values.len()), children)
+        }
+        DataType::Map(_, _) => {
+            let entries = as_map_array(array).value(row);
+            let entries = as_struct_array(&entries);
+            let (keys, values) = (entries.column(0), entries.column(1));
+            let children = (0..entries.len())
+                .map(|i| {
+                    let key = array_value_to_string(keys, i).unwrap_or_else(|e| e.to_string());
+                    value_tree(key, values, i)
+                })
+                .collect();
+            TreeNode::branch(format!("{}: {{{}}}", name, entries.len()), children)
+        }
+        _ => {
+            let value = array_value_to_string(array, row).unwrap_or_else(|e| e.to_string());
+            TreeNode::leaf(format!("{}: {}", name, value))
+        }
+    }
+}
+
+pub fn draw_inspector<B: Backend>(f: &mut Frame<B>, inspector: &CellInspector) {
+    let area = centered_rect(80, 80, f.size());
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Cell Inspector (Esc to close)");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
+        .split(inner);
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let header = Paragraph::new(vec![
+        Spans::from(vec![
+            Span::styled("Column: ", bold),
+            Span::raw(inspector.column.clone()),
+        ]),
+        Spans::from(vec![
+            Span::styled("Type: ", bold),
+            Span::raw(format!("{:?}", inspector.data_type)),
+        ]),
+    ]);
+    f.render_widget(header, chunks[0]);
+
+    match inspector.tree {
+        Some(ref tree) => draw_tree(f, tree, Block::default(), chunks[1]),
+        None => {
+            let value = Paragraph::new(inspector.value.clone()).wrap(Wrap { trim: false });
+            f.render_widget(value, chunks[1])
+        }
+    }
+}
+
+/// Rect covering `percent_x` by `percent_y` of `area`, centered within it
+pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_y) / 2),
+                Constraint::Percentage(percent_y),
+                Constraint::Percentage((100 - percent_y) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_x) / 2),
+                Constraint::Percentage(percent_x),
+                Constraint::Percentage((100 - percent_x) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(vertical[1])[1]
+}
@@ -0,0 +1,653 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+mod completion;
+pub mod file_picker;
+mod highlight;
+pub mod inspector;
+mod results;
+pub mod tree;
+
+use chrono::Local;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+    Frame,
+};
+use tui_logger::TuiLoggerWidget;
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::datafusion::config::ConfigSetting;
+use crate::app::ui::completion::draw_completion;
+use crate::app::ui::file_picker::draw_file_picker;
+use crate::app::ui::highlight::highlight_sql;
+use crate::app::ui::results::draw_query_results;
+use crate::app::ui::tree::draw_tree;
+use crate::app::{App, InputMode};
+
+/// Position of the results table viewport
+pub struct Scroll {
+    /// First visible column
+    pub x: usize,
+    /// First visible row
+    pub y: usize,
+}
+
+pub fn draw_ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    match app.tabs.index {
+        0 => draw_sql_eqitor_tab(f, app),
+        1 => draw_query_history_tab(f, app),
+        2 => draw_context_tab(f, app),
+        3 => draw_logs_tab(f, app),
+        4 => draw_catalog_tab(f, app),
+        5 => draw_plan_tab(f, app),
+        _ => draw_default_tab(f, app),
+    }
+    if let Some(ref picker) = app.file_picker {
+        draw_file_picker(f, picker);
+    }
+}
+
+fn draw_sql_eqitor_tab<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(20),
+                Constraint::Min(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let help_message = draw_help(app);
+    f.render_widget(help_message, chunks[0]);
+
+    let tabs = draw_tabs(app);
+    f.render_widget(tabs, chunks[1]);
+    let buffers = draw_buffers(app);
+    f.render_widget(buffers, chunks[2]);
+    let editor = draw_editor(app);
+    f.render_widget(editor, chunks[3]);
+    draw_cursor(app, f, &chunks);
+    draw_query_results(f, app, chunks[4]);
+    draw_completion(f, app, chunks[3]);
+}
+
+fn draw_query_history_tab<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Min(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let help_message = draw_help(app);
+    f.render_widget(help_message, chunks[0]);
+
+    let tabs = draw_tabs(app);
+    f.render_widget(tabs, chunks[1]);
+
+    let history_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+        .split(chunks[2]);
+    draw_query_history(f, app, history_chunks[0]);
+    draw_history_entry(f, app, history_chunks[1]);
+}
+
+fn draw_context_tab<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Min(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let help_message = draw_help(app);
+    f.render_widget(help_message, chunks[0]);
+
+    let tabs = draw_tabs(app);
+    f.render_widget(tabs, chunks[1]);
+
+    draw_context(f, app, chunks[2]);
+}
+
+fn draw_logs_tab<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Min(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let help_message = draw_help(app);
+    f.render_widget(help_message, chunks[0]);
+
+    let tabs = draw_tabs(app);
+    f.render_widget(tabs, chunks[1]);
+    let logs = draw_logs();
+    f.render_widget(logs, chunks[2])
+}
+
+fn draw_catalog_tab<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Min(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let help_message = draw_help(app);
+    f.render_widget(help_message, chunks[0]);
+
+    let tabs = draw_tabs(app);
+    f.render_widget(tabs, chunks[1]);
+
+    let block = Block::default().borders(Borders::ALL).title("Catalog");
+    if app.catalog.roots.is_empty() {
+        let text = Paragraph::new("No catalogs, the catalog is only available for local contexts");
+        f.render_widget(text.block(block), chunks[2]);
+    } else {
+        draw_tree(f, &app.catalog, block, chunks[2]);
+    }
+}
+
+fn draw_plan_tab<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Min(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let help_message = draw_help(app);
+    f.render_widget(help_message, chunks[0]);
+
+    let tabs = draw_tabs(app);
+    f.render_widget(tabs, chunks[1]);
+
+    let area = match app.plan_error {
+        Some(ref err_msg) => {
+            let areas = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(4), Constraint::Min(1)].as_ref())
+                .split(chunks[2]);
+            let text = Paragraph::new(err_msg.clone())
+                .style(Style::default().fg(Color::Red))
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Unable to plan the query in the editor"),
+                );
+            f.render_widget(text, areas[0]);
+            areas[1]
+        }
+        None => chunks[2],
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Plan of {}", app.editor.buffer().name));
+    if app.plan.roots.is_empty() {
+        let text = Paragraph::new("No query in the editor to plan");
+        f.render_widget(text.block(block), area);
+    } else {
+        draw_tree(f, &app.plan, block, area);
+    }
+}
+
+fn draw_default_tab<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Min(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let help_message = draw_help(app);
+    f.render_widget(help_message, chunks[0]);
+
+    let tabs = draw_tabs(app);
+    f.render_widget(tabs, chunks[1]);
+}
+
+fn draw_help<'a>(app: &mut App) -> Paragraph<'a> {
+    if let Some(ref prompt) = app.prompt {
+        return Paragraph::new(Spans::from(vec![
+            Span::styled(
+                prompt.label(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(prompt.input.clone()),
+        ]));
+    }
+    let (mut msg, style) = match app.input_mode {
+        InputMode::Normal => (
+            vec![
+                Span::raw("Press "),
+                Span::styled("q", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to exit, "),
+                Span::styled("e", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to start editing ("),
+                Span::styled("E", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" in $EDITOR), "),
+                Span::styled("c", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to clear the editor, "),
+                Span::styled("u", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to undo, "),
+                Span::styled("n", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" / "),
+                Span::styled("w", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" / "),
+                Span::styled("R", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" / "),
+                Span::styled("[ ]", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to open / close / rename / switch buffers, "),
+                Span::styled("Ctrl-o", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" / "),
+                Span::styled("Ctrl-s", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" / "),
+                Span::styled("S", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to open / save / save as a file."),
+            ],
+            Style::default().add_modifier(Modifier::RAPID_BLINK),
+        ),
+        InputMode::Editing => (
+            vec![
+                Span::raw("Press "),
+                Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to stop editing, "),
+                Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to execute query after closing ';' or a "),
+                Span::styled("\\", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" command ("),
+                Span::styled("\\?", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" lists them), "),
+                Span::styled("Tab", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to complete, "),
+                Span::styled("Ctrl-z", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" / "),
+                Span::styled("Ctrl-y", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to undo / redo, "),
+                Span::styled("Ctrl-o", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" / "),
+                Span::styled("Ctrl-s", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to open / save a file."),
+            ],
+            Style::default(),
+        ),
+    };
+    if app.is_query_running() {
+        msg.push(Span::raw(" Press "));
+        msg.push(Span::styled(
+            "Ctrl-c",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        msg.push(Span::raw(" to cancel the running query."));
+    } else if matches!(app.input_mode, InputMode::Normal)
+        && app.tabs.index == 0
+        && app.editor.buffer().query_results.is_some()
+    {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        msg.push(Span::raw(" Results: "));
+        msg.push(Span::styled("Enter", bold));
+        msg.push(Span::raw(" to inspect a cell, "));
+        msg.push(Span::styled("s", bold));
+        msg.push(Span::raw(" to sort, "));
+        msg.push(Span::styled("/", bold));
+        msg.push(Span::raw(" to filter, "));
+        msg.push(Span::styled("x", bold));
+        msg.push(Span::raw(" to export."));
+    } else if matches!(app.input_mode, InputMode::Normal) && app.tabs.index == 1 {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        msg.push(Span::raw(" History: "));
+        msg.push(Span::styled("/", bold));
+        msg.push(Span::raw(" to search, "));
+        msg.push(Span::styled("Enter", bold));
+        msg.push(Span::raw(" to edit a query, "));
+        msg.push(Span::styled("r", bold));
+        msg.push(Span::raw(" to re-run it."));
+    } else if matches!(app.input_mode, InputMode::Normal) && app.tabs.index == 2 {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        msg.push(Span::raw(" Context: "));
+        msg.push(Span::styled("Enter", bold));
+        msg.push(Span::raw(
+            " to change the selected setting or toggle the selected rule.",
+        ));
+    } else if matches!(app.input_mode, InputMode::Normal) && app.tabs.index == 4 {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        msg.push(Span::raw(" Catalog: "));
+        msg.push(Span::styled("Enter", bold));
+        msg.push(Span::raw(" to expand, "));
+        msg.push(Span::styled("s", bold));
+        msg.push(Span::raw(" to select from a table, "));
+        msg.push(Span::styled("r", bold));
+        msg.push(Span::raw(" to refresh."));
+    } else if matches!(app.input_mode, InputMode::Normal) && app.tabs.index == 5 {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        msg.push(Span::raw(" Plan: "));
+        msg.push(Span::styled("Enter", bold));
+        msg.push(Span::raw(" to expand, "));
+        msg.push(Span::styled("r", bold));
+        msg.push(Span::raw(" to re-plan the query in the editor."));
+    }
+    if let Some(ref status) = app.status_message {
+        let color = if status.is_error {
+            Color::Red
+        } else {
+            Color::Green
+        };
+        msg.push(Span::raw(" "));
+        msg.push(Span::styled(
+            status.text.clone(),
+            Style::default().fg(color),
+        ));
+    }
+    let mut text = Text::from(Spans::from(msg));
+    text.patch_style(style);
+    Paragraph::new(text)
+}
+
+fn draw_editor<'a>(app: &mut App) -> Paragraph<'a> {
+    let border_style = match app.input_mode {
+        InputMode::Normal => Style::default(),
+        InputMode::Editing => Style::default().fg(Color::Yellow),
+    };
+    let buffer = app.editor.buffer();
+    let mut title = match buffer.path {
+        Some(ref path) => format!("SQL Editor - {}", path.display()),
+        None => String::from("SQL Editor"),
+    };
+    if buffer.is_dirty() {
+        title.push_str(" *");
+    }
+    Paragraph::new(highlight_sql(&buffer.input.combine_lines())).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(title),
+    )
+}
+
+fn draw_cursor<B: Backend>(app: &mut App, f: &mut Frame<B>, chunks: &Vec<Rect>) {
+    if let Some(ref prompt) = app.prompt {
+        let width = prompt.label().width() + prompt.input.width();
+        f.set_cursor(chunks[0].x + width as u16, chunks[0].y);
+        return;
+    }
+    match app.input_mode {
+        InputMode::Normal =>
+            // Hide the cursor. `Frame` does this by default, so we don't need to do anything here
+            {}
+        InputMode::Editing => {
+            // Make the cursor visible and ask tui-rs to put it at the specified coordinates after rendering
+            f.set_cursor(
+                // Put cursor past the end of the input text
+                chunks[3].x + app.editor.get_cursor_column() + 1,
+                // Move one line down, from the border to the input line
+                chunks[3].y + app.editor.get_cursor_row() + 1,
+            )
+        }
+    };
+}
+
+fn draw_tabs<'a>(app: &mut App) -> Tabs<'a> {
+    let titles = app
+        .tabs
+        .titles
+        .iter()
+        .map(|t| Spans::from(vec![Span::styled(*t, Style::default())]))
+        .collect();
+
+    Tabs::new(titles)
+        .block(Block::default().borders(Borders::ALL).title("Tabs"))
+        .select(app.tabs.index)
+        .style(Style::default())
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+}
+
+/// Names of the editor buffers, with the active one highlighted
+fn draw_buffers<'a>(app: &mut App) -> Tabs<'a> {
+    let titles = app
+        .editor
+        .buffers
+        .iter()
+        .map(|b| {
+            let mut name = b.name.clone();
+            if b.is_dirty() {
+                name.push('*');
+            }
+            if b.query_status.is_running() {
+                name.push_str(" (running)");
+            }
+            Spans::from(Span::raw(name))
+        })
+        .collect();
+    Tabs::new(titles)
+        .select(app.editor.active)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+}
+
+fn draw_query_history<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let history = &app.editor.history;
+    let matches = app.history_browser.matches(history);
+    let messages: Vec<ListItem> = matches
+        .iter()
+        .map(|i| {
+            let m = &history.entries()[*i];
+            let status = if m.succeeded {
+                Span::raw(String::new())
+            } else {
+                Span::styled("[ Failed ] ", Style::default().fg(Color::Red))
+            };
+            let content = vec![
+                Spans::from(vec![
+                    status,
+                    Span::raw(format!(
+                        "Query {} [ {} rows took {:.3} seconds at {} ]",
+                        i,
+                        m.rows,
+                        m.query_duration,
+                        m.executed_at
+                            .with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M:%S")
+                    )),
+                ]),
+                Spans::from(Span::raw(m.query.replace('\n', " "))),
+                Spans::from(Span::raw(String::new())),
+            ];
+            ListItem::new(content)
+        })
+        .collect();
+
+    let title = if app.history_browser.search.is_empty() {
+        String::from("Query History")
+    } else {
+        format!(
+            "Query History [ {} matching '{}' ]",
+            matches.len(),
+            app.history_browser.search
+        )
+    };
+    let list = List::new(messages)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    if !matches.is_empty() {
+        state.select(Some(app.history_browser.selected));
+    }
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// Full text of the selected query and, if it failed, its error
+fn draw_history_entry<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title("Details");
+    let entry = match app.history_browser.selected_entry(&app.editor.history) {
+        Some(entry) => entry,
+        None => {
+            f.render_widget(Paragraph::new("No queries").block(block), area);
+            return;
+        }
+    };
+    let mut text = Text::raw(entry.query.clone());
+    if let Some(ref error) = entry.error {
+        text.extend(Text::raw("\n"));
+        text.extend(Text::styled(error.clone(), Style::default().fg(Color::Red)));
+    }
+    let details = Paragraph::new(text).block(block).wrap(Wrap { trim: false });
+    f.render_widget(details, area);
+}
+
+fn draw_logs<'a>() -> TuiLoggerWidget<'a> {
+    TuiLoggerWidget::default()
+        .style_error(Style::default().fg(Color::Red))
+        .style_debug(Style::default().fg(Color::Green))
+        .style_warn(Style::default().fg(Color::Yellow))
+        .style_trace(Style::default().fg(Color::Gray))
+        .style_info(Style::default().fg(Color::Blue))
+        .block(
+            Block::default()
+                .title("Logs")
+                .border_style(Style::default())
+                .borders(Borders::ALL),
+        )
+}
+
+fn draw_context<'a, B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let context = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let optimizers = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(context[1]);
+
+    // Only the list holding the selected item shows a selection
+    let settings = ConfigSetting::ALL.len();
+    let logical = app.optimizer_rules.logical.len();
+    let selected = app.context_selected;
+    let select = |start: usize, len: usize| {
+        let mut state = ListState::default();
+        if (start..start + len).contains(&selected) {
+            state.select(Some(selected - start));
+        }
+        state
+    };
+    let mut config_state = select(0, settings);
+    let mut logical_state = select(settings, logical);
+    let mut physical_state = select(settings + logical, app.optimizer_rules.physical.len());
+
+    let exec_config = draw_execution_config(app);
+    f.render_stateful_widget(exec_config, context[0], &mut config_state);
+
+    let rules: Vec<(&str, bool)> = app
+        .optimizer_rules
+        .logical
+        .iter()
+        .map(|(rule, enabled)| (rule.name(), *enabled))
+        .collect();
+    let logical_opts = draw_optimizer_rules("Logical Optimizers", rules);
+    f.render_stateful_widget(logical_opts, optimizers[0], &mut logical_state);
+
+    let rules: Vec<(&str, bool)> = app
+        .optimizer_rules
+        .physical
+        .iter()
+        .map(|(rule, enabled)| (rule.name(), *enabled))
+        .collect();
+    let physical_opts = draw_optimizer_rules("Physical Optimizers", rules);
+    f.render_stateful_widget(physical_opts, optimizers[1], &mut physical_state);
+}
+
+fn draw_execution_config(app: &mut App) -> List {
+    let exec_config = app.context.format_execution_config().unwrap_or_default();
+    let config: Vec<ListItem> = exec_config
+        .iter()
+        .map(|i| {
+            let content = vec![Spans::from(Span::raw(format!("{}", i)))];
+            ListItem::new(content)
+        })
+        .collect();
+
+    List::new(config)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("ExecutionConfig"),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+}
+
+/// Names of optimizer rules with a checkbox showing whether each is enabled
+fn draw_optimizer_rules<'a>(title: &'a str, rules: Vec<(&str, bool)>) -> List<'a> {
+    let opts: Vec<ListItem> = rules
+        .iter()
+        .map(|(name, enabled)| {
+            let checkbox = if *enabled { "[x] " } else { "[ ] " };
+            ListItem::new(Spans::from(Span::raw(format!("{}{}", checkbox, name))))
+        })
+        .collect();
+
+    List::new(opts)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+}
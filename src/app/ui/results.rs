@@ -0,0 +1,247 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Query results table
+
+use std::cmp;
+use std::ops::Range;
+
+use arrow::util::display::array_value_to_string;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::datafusion::context::{QueryResults, QueryResultsMeta};
+use crate::app::datafusion::query::QueryStatus;
+use crate::app::ui::inspector::draw_inspector;
+use crate::app::App;
+
+/// Columns wider than this are truncated in the table
+const MAX_COLUMN_WIDTH: usize = 40;
+
+/// Statements of a script listed above the results of its last statement
+const MAX_SUMMARY_LINES: usize = 5;
+
+pub fn draw_query_results<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let timing = app.timing;
+    let buffer = app.editor.buffer_mut();
+    let area = match buffer.statements.len() {
+        0 => area,
+        n => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Length(cmp::min(n, MAX_SUMMARY_LINES) as u16 + 1),
+                        Constraint::Min(1),
+                    ]
+                    .as_ref(),
+                )
+                .split(area);
+            draw_script_summary(f, &buffer.statements, timing, chunks[0]);
+            chunks[1]
+        }
+    };
+    let info = match buffer.query_results {
+        Some(ref results) if buffer.query_status.is_running() => results.format_row_count(),
+        Some(ref results) => results.format_timing_info(timing) + &results.format_view_info(),
+        None => String::new(),
+    };
+    let title = format!(
+        "Query Results {}{}",
+        buffer.query_status.format_status(),
+        info
+    );
+    let block = Block::default().borders(Borders::TOP).title(title);
+
+    let text = match (&buffer.query_status, &mut buffer.query_results) {
+        (QueryStatus::Running(_), None) => {
+            Paragraph::new("Query running, waiting for the first batch")
+        }
+        (QueryStatus::Cancelled, _) => Paragraph::new("Query cancelled"),
+        (QueryStatus::Failed(err_msg), _) => {
+            Paragraph::new(err_msg.clone()).style(Style::default().fg(Color::Red))
+        }
+        (_, Some(results)) if results.meta.query.starts_with("CREATE") => {
+            Paragraph::new("Table created")
+        }
+        (_, Some(results)) if results.batches.is_empty() => Paragraph::new("No rows returned"),
+        (_, Some(results)) => {
+            draw_results_table(f, results, block, area);
+            return;
+        }
+        (_, None) => Paragraph::new("No queries run from this buffer yet"),
+    };
+    f.render_widget(text.block(block), area);
+}
+
+/// One line for each of the statements that ran before the last statement of a script, the
+/// most recent ones are shown if they don't all fit
+fn draw_script_summary<B: Backend>(
+    f: &mut Frame<B>,
+    statements: &[QueryResultsMeta],
+    timing: bool,
+    area: Rect,
+) {
+    let skip = statements.len().saturating_sub(MAX_SUMMARY_LINES);
+    let lines: Vec<Spans> = statements
+        .iter()
+        .skip(skip)
+        .map(|meta| {
+            let query = meta.query.split_whitespace().collect::<Vec<_>>().join(" ");
+            let took = if timing {
+                format!(" in {:.3} seconds", meta.query_duration)
+            } else {
+                String::new()
+            };
+            Spans::from(vec![
+                Span::styled("OK ", Style::default().fg(Color::Green)),
+                Span::raw(format!(
+                    "{} {}{}: {}",
+                    meta.rows,
+                    if meta.rows == 1 { "row" } else { "rows" },
+                    took,
+                    query
+                )),
+            ])
+        })
+        .collect();
+    let title = format!("Script [ {} statements before the last ]", statements.len());
+    let summary = Paragraph::new(lines).block(Block::default().borders(Borders::TOP).title(title));
+    f.render_widget(summary, area);
+}
+
+/// Render the visible window of rows and columns, keeping the header pinned at the top
+fn draw_results_table<B: Backend>(
+    f: &mut Frame<B>,
+    results: &mut QueryResults,
+    block: Block,
+    area: Rect,
+) {
+    let inner = block.inner(area);
+    // Header and its bottom margin take two lines
+    results.page_size = inner.height.saturating_sub(2) as usize;
+    let start = results.scroll.y;
+    let end = cmp::min(start + results.page_size, results.retained_rows());
+
+    let mut columns = visible_columns(results, start..end, inner.width);
+    // Scroll right until the selected column fits
+    while results.selection.x >= results.scroll.x + columns.len() {
+        results.scroll.x += 1;
+        columns = visible_columns(results, start..end, inner.width);
+    }
+
+    let selected_col = results.selection.x - results.scroll.x;
+    let selected_row = results.selection.y;
+    let rows = (start..end).enumerate().map(|(i, row)| {
+        let cells = columns.iter().enumerate().map(|(col, column)| {
+            let cell = Cell::from(column.values[i].as_str());
+            if row == selected_row && col == selected_col {
+                cell.style(Style::default().add_modifier(Modifier::REVERSED))
+            } else {
+                cell
+            }
+        });
+        let style = if row == selected_row {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        Row::new(cells).style(style)
+    });
+    let headers = columns.iter().enumerate().map(|(col, column)| {
+        let style = if col == selected_col {
+            Style::default().add_modifier(Modifier::UNDERLINED)
+        } else {
+            Style::default()
+        };
+        Cell::from(column.header.as_str()).style(style)
+    });
+    let header = Row::new(headers)
+        .style(Style::default().add_modifier(Modifier::BOLD))
+        .bottom_margin(1);
+    let widths: Vec<Constraint> = columns
+        .iter()
+        .map(|column| Constraint::Length(column.width as u16))
+        .collect();
+    let table = Table::new(rows)
+        .header(header)
+        .block(block)
+        .widths(&widths)
+        .column_spacing(1);
+    f.render_widget(table, area);
+
+    if let Some(ref inspector) = results.inspector {
+        draw_inspector(f, inspector);
+    }
+}
+
+/// Formatted values of a column within the visible rows
+struct VisibleColumn {
+    header: String,
+    values: Vec<String>,
+    width: usize,
+}
+
+/// Columns, starting from the first scrolled column, that fit within `width`
+fn visible_columns(results: &QueryResults, rows: Range<usize>, width: u16) -> Vec<VisibleColumn> {
+    let schema = results.batches[0].schema();
+    let mut columns: Vec<VisibleColumn> = Vec::new();
+    let mut used_width = 0;
+    for col in results.scroll.x..results.num_columns() {
+        let header = schema.field(col).name().clone();
+        let values: Vec<String> = rows
+            .clone()
+            .map(|row| match results.locate_row(row) {
+                Some((batch, offset)) => format_cell(batch.column(col), offset),
+                None => String::new(),
+            })
+            .collect();
+        let column_width = values
+            .iter()
+            .map(|v| v.width())
+            .chain(std::iter::once(header.width()))
+            .max()
+            .map(|w| cmp::min(w, MAX_COLUMN_WIDTH))
+            .unwrap_or(0);
+        // Always show at least one column, even if it is wider than the table
+        if !columns.is_empty() && used_width + column_width > width as usize {
+            break;
+        }
+        used_width += column_width + 1;
+        columns.push(VisibleColumn {
+            header,
+            values,
+            width: column_width,
+        });
+    }
+    columns
+}
+
+/// Display value of a single cell, on one line
+pub fn format_cell(array: &arrow::array::ArrayRef, row: usize) -> String {
+    match array_value_to_string(array, row) {
+        Ok(value) => value.replace('\n', " "),
+        Err(e) => format!("<{}>", e),
+    }
+}
@@ -0,0 +1,245 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Collapsible tree view
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, List, ListItem, ListState},
+    Frame,
+};
+
+/// Single node of a `Tree` and its children
+pub struct TreeNode {
+    pub label: String,
+    pub children: Vec<TreeNode>,
+    pub expanded: bool,
+    /// Style of the node's label
+    pub style: Style,
+}
+
+impl TreeNode {
+    pub fn leaf(label: String) -> TreeNode {
+        TreeNode {
+            label,
+            children: Vec::new(),
+            expanded: false,
+            style: Style::default(),
+        }
+    }
+
+    pub fn branch(label: String, children: Vec<TreeNode>) -> TreeNode {
+        TreeNode {
+            label,
+            children,
+            expanded: false,
+            style: Style::default(),
+        }
+    }
+}
+
+/// Collapsible tree where only the children of expanded nodes are visible.  Nodes are
+/// addressed by their position in the list of visible nodes.
+pub struct Tree {
+    pub roots: Vec<TreeNode>,
+    /// Index of the selected visible node
+    pub selected: usize,
+}
+
+impl Tree {
+    pub fn new(roots: Vec<TreeNode>) -> Tree {
+        Tree { roots, selected: 0 }
+    }
+
+    /// Visible nodes in display order along with their depth
+    pub fn visible(&self) -> Vec<(usize, &TreeNode)> {
+        fn walk<'a>(nodes: &'a [TreeNode], depth: usize, out: &mut Vec<(usize, &'a TreeNode)>) {
+            for node in nodes {
+                out.push((depth, node));
+                if node.expanded {
+                    walk(&node.children, depth + 1, out)
+                }
+            }
+        }
+        let mut out = Vec::new();
+        walk(&self.roots, 0, &mut out);
+        out
+    }
+
+    /// Path of child indexes from the roots to the visible node at `index`
+    pub fn path(&self, index: usize) -> Option<Vec<usize>> {
+        fn walk(nodes: &[TreeNode], remaining: &mut usize, path: &mut Vec<usize>) -> bool {
+            for (i, node) in nodes.iter().enumerate() {
+                path.push(i);
+                if *remaining == 0 {
+                    return true;
+                }
+                *remaining -= 1;
+                if node.expanded && walk(&node.children, remaining, path) {
+                    return true;
+                }
+                path.pop();
+            }
+            false
+        }
+        let mut remaining = index;
+        let mut path = Vec::new();
+        if walk(&self.roots, &mut remaining, &mut path) {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    pub fn selected_node(&self) -> Option<&TreeNode> {
+        let path = self.path(self.selected)?;
+        let mut nodes = &self.roots;
+        let mut node = None;
+        for i in path {
+            node = nodes.get(i);
+            nodes = &node?.children;
+        }
+        node
+    }
+
+    /// Labels of the selected node and its ancestors, starting from its root
+    pub fn selected_labels(&self) -> Vec<&str> {
+        let mut labels = Vec::new();
+        if let Some(path) = self.path(self.selected) {
+            let mut nodes = &self.roots;
+            for i in path {
+                let node = &nodes[i];
+                labels.push(node.label.as_str());
+                nodes = &node.children;
+            }
+        }
+        labels
+    }
+
+    fn selected_node_mut(&mut self) -> Option<&mut TreeNode> {
+        let path = self.path(self.selected)?;
+        let (first, rest) = path.split_first()?;
+        let mut node = self.roots.get_mut(*first)?;
+        for i in rest {
+            node = node.children.get_mut(*i)?;
+        }
+        Some(node)
+    }
+
+    pub fn next(&mut self) {
+        if self.selected + 1 < self.visible().len() {
+            self.selected += 1
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1)
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some(node) = self.selected_node_mut() {
+            node.expanded = !node.expanded
+        }
+    }
+
+    pub fn expand(&mut self) {
+        if let Some(node) = self.selected_node_mut() {
+            node.expanded = true
+        }
+    }
+
+    pub fn collapse(&mut self) {
+        if let Some(node) = self.selected_node_mut() {
+            node.expanded = false
+        }
+    }
+}
+
+pub fn draw_tree<B: Backend>(f: &mut Frame<B>, tree: &Tree, block: Block, area: Rect) {
+    let items: Vec<ListItem> = tree
+        .visible()
+        .iter()
+        .map(|(depth, node)| {
+            let marker = match (node.children.is_empty(), node.expanded) {
+                (true, _) => "  ",
+                (false, true) => "▾ ",
+                (false, false) => "▸ ",
+            };
+            ListItem::new(format!("{}{}{}", "  ".repeat(*depth), marker, node.label))
+                .style(node.style)
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    state.select(Some(tree.selected));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> Tree {
+        Tree::new(vec![
+            TreeNode::branch(
+                "a".to_string(),
+                vec![
+                    TreeNode::leaf("a.1".to_string()),
+                    TreeNode::leaf("a.2".to_string()),
+                ],
+            ),
+            TreeNode::leaf("b".to_string()),
+        ])
+    }
+
+    fn labels(tree: &Tree) -> Vec<&str> {
+        tree.visible()
+            .iter()
+            .map(|(_, node)| node.label.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_toggle_shows_and_hides_children() {
+        let mut tree = tree();
+        assert_eq!(vec!["a", "b"], labels(&tree));
+
+        tree.toggle();
+        assert_eq!(vec!["a", "a.1", "a.2", "b"], labels(&tree));
+
+        tree.toggle();
+        assert_eq!(vec!["a", "b"], labels(&tree));
+    }
+
+    #[test]
+    fn test_selection_follows_visible_nodes() {
+        let mut tree = tree();
+        tree.expand();
+        tree.next();
+        tree.next();
+        assert_eq!("a.2", tree.selected_node().unwrap().label);
+        tree.next();
+        tree.next();
+        assert_eq!("b", tree.selected_node().unwrap().label);
+        tree.previous();
+        assert_eq!("a.2", tree.selected_node().unwrap().label);
+    }
+}
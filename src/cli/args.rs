@@ -58,6 +58,20 @@ pub struct Args {
     )]
     pub rc: Option<Vec<String>>,
 
+    #[clap(
+        long,
+        help = "Maximum number of result rows kept in memory for display",
+        default_value_t = 10000
+    )]
+    pub max_display_rows: usize,
+
+    #[clap(
+        long,
+        help = "Maximum number of queries kept in the history file, 0 disables saving history",
+        default_value_t = 1000
+    )]
+    pub max_history: usize,
+
     #[clap(long, arg_enum, default_value_t = PrintFormat::Table)]
     pub format: PrintFormat,
 
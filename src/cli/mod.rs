@@ -17,3 +17,4 @@
 
 pub mod args;
 pub mod print_format;
+pub mod print_options;
@@ -17,10 +17,16 @@
 
 //! Print format variants
 use arrow::csv::writer::WriterBuilder;
+use arrow::datatypes::SchemaRef;
+use arrow::ipc::writer::FileWriter;
 use arrow::json::{ArrayWriter, LineDelimitedWriter};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::arrow::util::pretty;
 use datafusion::error::{DataFusionError, Result};
+use datafusion::parquet::arrow::ArrowWriter;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
 use std::str::FromStr;
 
 /// Allow records to be printed in different formats
@@ -83,6 +89,117 @@ impl PrintFormat {
         }
         Ok(())
     }
+
+    /// format the batches as text using the specified format
+    pub fn format_batches(&self, batches: &[RecordBatch]) -> Result<String> {
+        Ok(match self {
+            Self::Csv => print_batches_with_sep(batches, b',')?,
+            Self::Tsv => print_batches_with_sep(batches, b'\t')?,
+            Self::Table => pretty::pretty_format_batches(batches)?.to_string(),
+            Self::Json => batches_to_json!(ArrayWriter, batches),
+            Self::NdJson => batches_to_json!(LineDelimitedWriter, batches),
+        })
+    }
+}
+
+/// Formats records can be exported to a file in, the print formats and binary formats which
+/// can only be written to a file
+#[derive(Debug, PartialEq, Eq, clap::ArgEnum, Clone)]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    Table,
+    Json,
+    NdJson,
+    Parquet,
+    Arrow,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        clap::ArgEnum::from_str(s, true)
+    }
+}
+
+impl From<PrintFormat> for ExportFormat {
+    fn from(format: PrintFormat) -> Self {
+        match format {
+            PrintFormat::Csv => Self::Csv,
+            PrintFormat::Tsv => Self::Tsv,
+            PrintFormat::Table => Self::Table,
+            PrintFormat::Json => Self::Json,
+            PrintFormat::NdJson => Self::NdJson,
+        }
+    }
+}
+
+impl ExportFormat {
+    /// write the batches to the file at `path` using the specified format, the file is only
+    /// created once the batches have been formatted
+    pub fn write_batches(&self, batches: &[RecordBatch], path: &str) -> Result<()> {
+        let format = match self {
+            Self::Csv => PrintFormat::Csv,
+            Self::Tsv => PrintFormat::Tsv,
+            Self::Table => PrintFormat::Table,
+            Self::Json => PrintFormat::Json,
+            Self::NdJson => PrintFormat::NdJson,
+            Self::Parquet => return write_parquet(path, first_schema(batches)?, batches),
+            Self::Arrow => return write_ipc(path, first_schema(batches)?, batches),
+        };
+        let text = format.format_batches(batches)?;
+        File::create(path)?.write_all(text.as_bytes())?;
+        Ok(())
+    }
+
+    /// name of the format as accepted by the export prompt
+    pub fn name(&self) -> &'static str {
+        clap::ArgEnum::to_possible_value(self).unwrap().get_name()
+    }
+
+    /// format implied by the extension of `path`, if it is recognised
+    pub fn from_extension(path: &str) -> Option<ExportFormat> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "tsv" => Some(Self::Tsv),
+            "txt" => Some(Self::Table),
+            "json" => Some(Self::Json),
+            "ndjson" | "jsonl" => Some(Self::NdJson),
+            "parquet" => Some(Self::Parquet),
+            "arrow" | "ipc" | "feather" => Some(Self::Arrow),
+            _ => None,
+        }
+    }
+}
+
+/// Binary formats need a schema up front, which is taken from the first batch
+fn first_schema(batches: &[RecordBatch]) -> Result<SchemaRef> {
+    match batches.first() {
+        Some(batch) => Ok(batch.schema()),
+        None => Err(DataFusionError::Execution(
+            "No batches to write".to_string(),
+        )),
+    }
+}
+
+fn write_parquet(path: &str, schema: SchemaRef, batches: &[RecordBatch]) -> Result<()> {
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema, None)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.close()?;
+    Ok(())
+}
+
+fn write_ipc(path: &str, schema: SchemaRef, batches: &[RecordBatch]) -> Result<()> {
+    let mut writer = FileWriter::try_new(File::create(path)?, &schema)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.finish()?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -158,4 +275,65 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_from_extension() {
+        assert_eq!(
+            Some(ExportFormat::Csv),
+            ExportFormat::from_extension("out.CSV")
+        );
+        assert_eq!(
+            Some(ExportFormat::NdJson),
+            ExportFormat::from_extension("/tmp/out.jsonl")
+        );
+        assert_eq!(
+            Some(ExportFormat::Parquet),
+            ExportFormat::from_extension("out.parquet")
+        );
+        assert_eq!(None, ExportFormat::from_extension("out"));
+        assert_eq!(None, ExportFormat::from_extension("out.xlsx"));
+    }
+
+    #[test]
+    fn test_write_batches() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from_slice([1, 2])),
+                Arc::new(Int32Array::from_slice([3, 4])),
+            ],
+        )
+        .unwrap();
+        let batches = vec![batch];
+
+        let dir = tempfile::tempdir()?;
+        let dir = dir.path();
+
+        let path = dir.join("out.csv");
+        let path = path.to_str().unwrap();
+        ExportFormat::Csv.write_batches(&batches, path)?;
+        assert_eq!("a,b\n1,3\n2,4\n", std::fs::read_to_string(path)?);
+
+        for format in [ExportFormat::Parquet, ExportFormat::Arrow] {
+            let path = dir.join(format!("out.{:?}", format));
+            let path = path.to_str().unwrap();
+            format.write_batches(&batches, path)?;
+            assert!(std::fs::metadata(path)?.len() > 0);
+        }
+
+        // An existing file is left alone when there is nothing to export
+        let path = dir.join("empty.parquet");
+        std::fs::write(&path, "previous")?;
+        let empty: Vec<RecordBatch> = vec![];
+        assert!(ExportFormat::Parquet
+            .write_batches(&empty, path.to_str().unwrap())
+            .is_err());
+        assert_eq!("previous", std::fs::read_to_string(&path)?);
+        Ok(())
+    }
 }
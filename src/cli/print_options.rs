@@ -0,0 +1,48 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Options for printing the results of statements run outside of the TUI
+
+use std::time::Instant;
+
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::Result;
+
+use super::print_format::PrintFormat;
+
+#[derive(Debug, Clone)]
+pub struct PrintOptions {
+    pub format: PrintFormat,
+    pub quiet: bool,
+}
+
+impl PrintOptions {
+    /// print the batches to stdout, followed by timing info unless running quietly
+    pub fn print_batches(&self, batches: &[RecordBatch], query_start_time: Instant) -> Result<()> {
+        let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+        self.format.print_batches(batches)?;
+        if !self.quiet {
+            println!(
+                "{} {} in set. Query took {:.3} seconds.",
+                row_count,
+                if row_count == 1 { "row" } else { "rows" },
+                query_start_time.elapsed().as_secs_f64()
+            );
+        }
+        Ok(())
+    }
+}
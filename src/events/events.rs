@@ -16,6 +16,8 @@
 // under the License.
 
 use crossterm::event;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 use std::{sync::mpsc, thread};
 
@@ -30,15 +32,32 @@ pub struct Events {
     rx: mpsc::Receiver<Event>,
     // Need to be kept around to prevent disposing the sender side.
     _tx: mpsc::Sender<Event>,
+    /// Set to stop the input thread reading from the terminal
+    paused: Arc<AtomicBool>,
+    /// Set by the input thread while it may be reading from the terminal
+    reading: Arc<AtomicBool>,
 }
 
 impl Events {
     pub fn new(tick_rate: Duration) -> Events {
         let (tx, rx) = mpsc::channel();
 
+        let paused = Arc::new(AtomicBool::new(false));
+        let reading = Arc::new(AtomicBool::new(false));
+
         let event_tx = tx.clone(); // the thread::spawn own event_tx
+        let thread_paused = paused.clone();
+        let thread_reading = reading.clone();
         thread::spawn(move || {
             loop {
+                // Flag that input may be read before checking for a pause, so that `pause`
+                // either sees the flag or the thread sees the pause
+                thread_reading.store(true, Ordering::SeqCst);
+                if thread_paused.load(Ordering::SeqCst) {
+                    thread_reading.store(false, Ordering::SeqCst);
+                    thread::sleep(tick_rate);
+                    continue;
+                }
                 // poll for tick rate duration, if no event, sent tick event.
                 if crossterm::event::poll(tick_rate).unwrap() {
                     if let event::Event::Key(key) = event::read().unwrap() {
@@ -46,11 +65,30 @@ impl Events {
                         event_tx.send(Event::KeyInput(key)).unwrap();
                     }
                 }
+                thread_reading.store(false, Ordering::SeqCst);
                 event_tx.send(Event::Tick).unwrap();
             }
         });
 
-        Events { rx, _tx: tx }
+        Events {
+            rx,
+            _tx: tx,
+            paused,
+            reading,
+        }
+    }
+
+    /// Stop reading terminal input, e.g. while another program uses the terminal.  Blocks
+    /// until a read in progress has finished.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        while self.reading.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
     }
 
     /// Attempts to read an event.
@@ -28,10 +28,16 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use tui::{backend::CrosstermBackend, Terminal};
+use tui::{
+    backend::{Backend, CrosstermBackend},
+    Terminal,
+};
 
+use crate::app::datafusion::context::Context;
 use crate::app::ui;
 use crate::app::App;
+use crate::cli::args::Args;
+use crate::cli::print_options::PrintOptions;
 
 use crate::events::{Event, Events};
 
@@ -55,8 +61,17 @@ pub async fn run_app(app: &mut App) -> io::Result<()> {
             Event::Tick => app.update_on_tick(),
         };
 
-        if result == AppReturn::Exit {
-            break;
+        match result {
+            AppReturn::Continue => {}
+            AppReturn::Exit => break,
+            AppReturn::EditExternally => {
+                // Stop reading keys so they all go to the editor
+                events.pause();
+                suspend_terminal(&mut terminal)?;
+                app.edit_externally();
+                resume_terminal(&mut terminal)?;
+                events.resume();
+            }
         }
     }
 
@@ -69,3 +84,36 @@ pub async fn run_app(app: &mut App) -> io::Result<()> {
     terminal.show_cursor()?;
     Ok(())
 }
+
+/// Hand the terminal back to the shell, as is done on exit
+fn suspend_terminal<B: Backend + io::Write>(terminal: &mut Terminal<B>) -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()
+}
+
+fn resume_terminal<B: Backend + io::Write>(terminal: &mut Terminal<B>) -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+    // Redraw everything, the screen was used by another program
+    terminal.clear()
+}
+
+/// Execute the statements in the files passed with `--file`, printing their results, without
+/// starting the TUI.  Returns the number of statements that failed.
+pub async fn run_files(args: Args) -> usize {
+    let mut ctx = Context::from_args(&args).await;
+    let print_options = PrintOptions {
+        format: args.format,
+        quiet: args.quiet,
+    };
+    ctx.exec_files_and_print(args.file, &print_options).await
+}
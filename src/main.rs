@@ -21,7 +21,7 @@ use std::error::Error;
 use clap::Parser;
 use datafusion_tui::app::App;
 use datafusion_tui::cli::args::Args;
-use datafusion_tui::run_app;
+use datafusion_tui::{run_app, run_files};
 use log::LevelFilter;
 use mimalloc::MiMalloc;
 
@@ -33,6 +33,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
     tui_logger::init_logger(LevelFilter::Trace).unwrap();
     tui_logger::set_default_level(LevelFilter::Trace);
     let args = Args::parse();
+    if !args.file.is_empty() {
+        // Batch mode, the terminal is left untouched
+        let failures = run_files(args).await;
+        std::process::exit(if failures == 0 { 0 } else { 1 });
+    }
     let mut app = App::new(args).await;
     let res = run_app(&mut app).await;
 